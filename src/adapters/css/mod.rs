@@ -0,0 +1,64 @@
+mod parser;
+mod resolver;
+
+use crate::config::CssConfig;
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
+use anyhow::Result;
+use async_trait::async_trait;
+use parser::{CssParser, Syntax};
+use resolver::CssResolver;
+use std::path::Path;
+
+#[derive(Clone, Default)]
+pub struct CssAdapter {
+    parser: CssParser,
+    resolver: CssResolver,
+}
+
+impl CssAdapter {
+    pub fn new() -> Self {
+        Self {
+            parser: CssParser::new(),
+            resolver: CssResolver::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: &CssConfig) -> Self {
+        self.resolver = self.resolver.with_config(config);
+        self
+    }
+}
+
+#[async_trait]
+impl LanguageAdapter for CssAdapter {
+    fn name(&self) -> &str {
+        "CSS"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["css", "scss", "sass", "less"]
+    }
+
+    async fn parse_imports(
+        &self,
+        file_path: &Path,
+        content: &str,
+        _context: &AnalysisContext,
+    ) -> Result<Vec<ImportStatement>> {
+        let syntax = file_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Syntax::from_extension)
+            .unwrap_or(Syntax::Css);
+        Ok(self.parser.parse(content, syntax))
+    }
+
+    async fn resolve_import(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Option<ResolvedImport>> {
+        self.resolver.resolve(import, from_file, &*context.fs).await
+    }
+}
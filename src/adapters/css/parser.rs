@@ -0,0 +1,397 @@
+use crate::adapters::line_index::LineIndex;
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
+
+/// Kind of `@import` rules
+pub const CSS_IMPORT: &str = "@import";
+/// Kind of Sass `@use` rules
+pub const SASS_USE: &str = "@use";
+
+/// Stylesheet dialects, which differ in comment syntax and in how imports
+/// without an extension are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Css,
+    Scss,
+    /// Indented Sass syntax, where rules end at the line break
+    Sass,
+    Less,
+}
+
+impl Syntax {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "css" => Some(Syntax::Css),
+            "scss" => Some(Syntax::Scss),
+            "sass" => Some(Syntax::Sass),
+            "less" => Some(Syntax::Less),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct CssParser;
+
+impl CssParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Finds `@import`, `@use` and `@forward` rules and `url()` references
+    /// outside comments. `url()` values are assets; data URIs and values built
+    /// from variables or interpolation are skipped.
+    pub fn parse(&self, content: &str, syntax: Syntax) -> Vec<ImportStatement> {
+        let chars: Vec<char> = content.chars().collect();
+        let lines = LineIndex::new(&chars);
+
+        let mut imports = Vec::new();
+        let mut depth = 0usize;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    i = (i + 2..chars.len())
+                        .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                        .map_or(chars.len(), |j| j + 2);
+                }
+                // `//` inside parentheses is part of a URL
+                '/' if chars.get(i + 1) == Some(&'/') && syntax != Syntax::Css && depth == 0 => {
+                    i = line_end(&chars, i);
+                }
+                '"' | '\'' => i = string_end(&chars, i),
+                '(' => {
+                    depth += 1;
+                    i += 1;
+                }
+                ')' => {
+                    depth = depth.saturating_sub(1);
+                    i += 1;
+                }
+                '@' => {
+                    let name_len = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| c.is_alphanumeric() || **c == '-')
+                        .count();
+                    let name: String = chars[i + 1..i + 1 + name_len].iter().collect();
+                    if !matches!(name.as_str(), "import" | "use" | "forward") {
+                        i += 1 + name_len;
+                        continue;
+                    }
+
+                    let end = prelude_end(&chars, i + 1 + name_len, syntax);
+                    let prelude: String = chars[i + 1 + name_len..end].iter().collect();
+                    let raw: String = chars[i..end].iter().collect::<String>();
+                    let raw = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+                    let (line, column) = lines.position(i);
+                    let statement =
+                        |specifier: String, kind: ImportKind, bindings| ImportStatement {
+                            specifier,
+                            kind,
+                            line,
+                            column,
+                            raw: raw.clone(),
+                            bindings,
+                        };
+
+                    match name.as_str() {
+                        "import" => {
+                            for specifier in import_targets(&prelude, syntax) {
+                                imports.push(statement(
+                                    specifier,
+                                    ImportKind::Custom(CSS_IMPORT.to_string()),
+                                    Vec::new(),
+                                ));
+                            }
+                        }
+                        "use" => {
+                            if let Some(specifier) = first_string(&prelude) {
+                                let bindings = use_bindings(&specifier, &prelude);
+                                imports.push(statement(
+                                    specifier,
+                                    ImportKind::Custom(SASS_USE.to_string()),
+                                    bindings,
+                                ));
+                            }
+                        }
+                        _ => {
+                            if let Some(specifier) = first_string(&prelude) {
+                                imports.push(statement(
+                                    specifier,
+                                    ImportKind::ReExport,
+                                    vec![ImportBinding::ReExportAll],
+                                ));
+                            }
+                        }
+                    }
+                    i = end;
+                }
+                'u' | 'U'
+                    if chars[i..].len() >= 4
+                        && chars[i..i + 4]
+                            .iter()
+                            .collect::<String>()
+                            .eq_ignore_ascii_case("url(")
+                        && (i == 0 || !is_ident_char(chars[i - 1])) =>
+                {
+                    let close = (i + 4..chars.len())
+                        .find(|&j| chars[j] == ')')
+                        .unwrap_or(chars.len());
+                    let value: String = chars[i + 4..close].iter().collect();
+                    let value = unquote(value.trim());
+                    if is_followable_url(value) {
+                        let (line, column) = lines.position(i);
+                        imports.push(ImportStatement {
+                            specifier: value.to_string(),
+                            kind: ImportKind::Asset,
+                            line,
+                            column,
+                            raw: chars[i..(close + 1).min(chars.len())].iter().collect(),
+                            bindings: Vec::new(),
+                        });
+                    }
+                    i = close + 1;
+                }
+                _ => i += 1,
+            }
+        }
+
+        imports
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn line_end(chars: &[char], from: usize) -> usize {
+    (from..chars.len())
+        .find(|&j| chars[j] == '\n')
+        .unwrap_or(chars.len())
+}
+
+/// Index just past the string literal starting at `start`
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut j = start + 1;
+    while j < chars.len() && chars[j] != quote && chars[j] != '\n' {
+        if chars[j] == '\\' {
+            j += 1;
+        }
+        j += 1;
+    }
+    (j + 1).min(chars.len())
+}
+
+/// Index of the `;` or `{` ending an at-rule's prelude, or of the line break
+/// in the indented syntax
+fn prelude_end(chars: &[char], from: usize, syntax: Syntax) -> usize {
+    let mut depth = 0usize;
+    let mut j = from;
+    while j < chars.len() {
+        match chars[j] {
+            '"' | '\'' => {
+                j = string_end(chars, j);
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' | '{' | '}' if depth == 0 => return j,
+            '\n' if syntax == Syntax::Sass && depth == 0 => return j,
+            _ => {}
+        }
+        j += 1;
+    }
+    chars.len()
+}
+
+/// The stylesheets an `@import` prelude names: quoted strings and `url()`
+/// values separated by commas. Less options like `(reference)` and media
+/// queries after the target are ignored.
+fn import_targets(prelude: &str, syntax: Syntax) -> Vec<String> {
+    split_top_level(prelude)
+        .into_iter()
+        .filter_map(|item| {
+            let mut item = item.trim();
+            if item.starts_with('(') {
+                item = item.split_once(')').map_or("", |(_, rest)| rest.trim());
+            }
+            if let Some(value) = first_string(item).filter(|_| item.starts_with(['"', '\''])) {
+                return Some(value);
+            }
+            if item.len() >= 4 && item[..4].eq_ignore_ascii_case("url(") {
+                let value = item[4..].split(')').next().unwrap_or("");
+                return Some(unquote(value.trim()).to_string());
+            }
+            // The indented syntax allows unquoted imports
+            (syntax == Syntax::Sass)
+                .then(|| item.split_whitespace().next())
+                .flatten()
+                .map(str::to_string)
+        })
+        .filter(|target| !target.is_empty())
+        .collect()
+}
+
+/// Splits on commas outside strings and parentheses
+fn split_top_level(prelude: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for c in prelude.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(current);
+    items
+}
+
+fn first_string(text: &str) -> Option<String> {
+    let start = text.find(['"', '\''])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix(['"', '\''])
+        .and_then(|v| v.strip_suffix(['"', '\'']))
+        .unwrap_or(value)
+}
+
+/// `@use "path" as name` binds `name`, `as *` binds nothing, and otherwise
+/// the last path segment without its extension or partial underscore
+fn use_bindings(specifier: &str, prelude: &str) -> Vec<ImportBinding> {
+    let mut words = prelude.split_whitespace().skip_while(|w| *w != "as");
+    let local = match (words.next(), words.next()) {
+        (Some("as"), Some("*")) => return Vec::new(),
+        (Some("as"), Some(name)) => name.trim_end_matches(';').to_string(),
+        _ => {
+            let segment = specifier.rsplit('/').next().unwrap_or(specifier);
+            let segment = segment.split('.').next().unwrap_or(segment);
+            segment.trim_start_matches('_').to_string()
+        }
+    };
+    vec![ImportBinding::Namespace {
+        local,
+        type_only: false,
+    }]
+}
+
+/// Whether a `url()` value could name a file: not a data URI, fragment or
+/// value assembled from variables and interpolation
+fn is_followable_url(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with("data:")
+        && !value.starts_with('#')
+        && !value.contains("#{")
+        && !value.contains(['$', '@', '+'])
+        && !value.starts_with("var(")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifiers(content: &str, syntax: Syntax) -> Vec<String> {
+        CssParser::new()
+            .parse(content, syntax)
+            .into_iter()
+            .map(|i| i.specifier)
+            .collect()
+    }
+
+    fn namespace(local: &str) -> Vec<ImportBinding> {
+        vec![ImportBinding::Namespace {
+            local: local.into(),
+            type_only: false,
+        }]
+    }
+
+    #[test]
+    fn test_import_lists_and_options() {
+        assert_eq!(
+            specifiers(
+                "@import \"reset\", 'base/typography' screen;\n@import url(\"print.css\") print;",
+                Syntax::Scss
+            ),
+            vec!["reset", "base/typography", "print.css"]
+        );
+        assert_eq!(
+            specifiers("@import (reference) \"theme\";", Syntax::Less),
+            vec!["theme"]
+        );
+        // The indented syntax allows unquoted imports ending at the line break
+        assert_eq!(
+            specifiers("@import partials/grid\n.a\n  color: red", Syntax::Sass),
+            vec!["partials/grid"]
+        );
+    }
+
+    #[test]
+    fn test_use_and_forward_bindings() {
+        let imports = CssParser::new().parse(
+            "@use 'config/_colors.scss';\n@use \"config/spacing\" as s;\n@use \"mixins\" as *;\n@forward \"functions\";",
+            Syntax::Scss,
+        );
+
+        assert_eq!(imports[0].bindings, namespace("colors"));
+        assert_eq!(imports[1].bindings, namespace("s"));
+        assert!(imports[2].bindings.is_empty());
+        assert!(matches!(imports[3].kind, ImportKind::ReExport));
+        assert_eq!(imports[3].bindings, vec![ImportBinding::ReExportAll]);
+    }
+
+    #[test]
+    fn test_comments_depend_on_syntax() {
+        let content = "/* @import \"block\"; */\n// @import \"line\";\n.a { background: url(//cdn.example.com/bg.png); }";
+
+        assert_eq!(
+            specifiers(content, Syntax::Scss),
+            vec!["//cdn.example.com/bg.png"]
+        );
+        // Plain CSS has no line comments
+        assert_eq!(
+            specifiers(content, Syntax::Css),
+            vec!["line", "//cdn.example.com/bg.png"]
+        );
+    }
+
+    #[test]
+    fn test_only_literal_urls_are_assets() {
+        let content = r#".logo {
+  background: url(../img/logo.png?v=2) no-repeat;
+  mask: url("data:image/svg+xml;utf8,<svg/>");
+  cursor: url(#{$cursor-path}/hand.cur), url($fallback), url(var(--icon)), url(#clip);
+  content: "url(not-a-url)";
+  filter: URL('filters.svg');
+}"#;
+        let imports = CssParser::new().parse(content, Syntax::Scss);
+        let summary: Vec<(&str, usize, usize)> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.line, i.column))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![("../img/logo.png?v=2", 2, 14), ("filters.svg", 6, 10)]
+        );
+        assert!(imports.iter().all(|i| matches!(i.kind, ImportKind::Asset)));
+    }
+}
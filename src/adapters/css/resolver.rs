@@ -0,0 +1,268 @@
+use super::parser::Syntax;
+use crate::config::CssConfig;
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportKind, ImportStatement, ResolvedImport};
+use anyhow::Result;
+use path_absolutize::Absolutize;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Default)]
+pub struct CssResolver {
+    include_paths: Vec<PathBuf>,
+}
+
+impl CssResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, config: &CssConfig) -> Self {
+        self.include_paths = config.include_paths.clone().unwrap_or_default();
+        self
+    }
+
+    /// Resolves stylesheet imports against the importing file's directory,
+    /// then the include paths, trying Sass partials and index files for
+    /// imports without an extension. `url()` references resolve to assets
+    /// next to the importing file. Remote URLs, built-in Sass modules and
+    /// `~` package imports are external.
+    pub async fn resolve(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<ResolvedImport>> {
+        let specifier = import.specifier.as_str();
+        if is_external(specifier) {
+            log::debug!("Skipping external stylesheet reference '{}'", specifier);
+            return Ok(None);
+        }
+
+        let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+
+        if matches!(import.kind, ImportKind::Asset) {
+            let path = specifier.split(['?', '#']).next().unwrap_or(specifier);
+            let asset = from_dir.join(path);
+            if path.starts_with('/') || !fs.exists(&asset).await {
+                log::debug!(
+                    "Asset '{}' from {} not found",
+                    specifier,
+                    from_file.display()
+                );
+                return Ok(None);
+            }
+            return Ok(Some(ResolvedImport {
+                path: asset.absolutize()?.to_path_buf(),
+                is_local: true,
+                is_asset: true,
+            }));
+        }
+
+        let syntax = from_file
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Syntax::from_extension)
+            .unwrap_or(Syntax::Css);
+
+        for root in std::iter::once(from_dir).chain(self.include_paths.iter().map(PathBuf::as_path))
+        {
+            if let Some(path) = find_stylesheet(&root.join(specifier), syntax, fs).await {
+                return Ok(Some(ResolvedImport {
+                    path: path.absolutize()?.to_path_buf(),
+                    is_local: true,
+                    is_asset: false,
+                }));
+            }
+        }
+
+        log::debug!(
+            "Treating '{}' from {} as external",
+            specifier,
+            from_file.display()
+        );
+        Ok(None)
+    }
+}
+
+fn is_external(specifier: &str) -> bool {
+    specifier.starts_with("http:")
+        || specifier.starts_with("https:")
+        || specifier.starts_with("//")
+        || specifier.starts_with("sass:")
+        || specifier.starts_with("pkg:")
+        || specifier.starts_with('~')
+}
+
+/// Extensions tried for an import without one, in order
+fn extensions(syntax: Syntax) -> &'static [&'static str] {
+    match syntax {
+        Syntax::Css => &["css"],
+        Syntax::Scss | Syntax::Sass => &["scss", "sass", "css"],
+        Syntax::Less => &["less", "css"],
+    }
+}
+
+/// The file an import of `base` loads: `base` itself, `base` with an
+/// extension (and, for Sass, as a `_` partial), or an index file inside it
+async fn find_stylesheet(
+    base: &Path,
+    syntax: Syntax,
+    fs: &dyn FileSystemProvider,
+) -> Option<PathBuf> {
+    let has_extension = base
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| Syntax::from_extension(e).is_some());
+    if has_extension {
+        if fs.exists(base).await && !fs.is_directory(base).await {
+            return Some(base.to_path_buf());
+        }
+        if !matches!(syntax, Syntax::Scss | Syntax::Sass) {
+            return None;
+        }
+    }
+
+    let partials = matches!(syntax, Syntax::Scss | Syntax::Sass);
+    let dir = base.parent()?;
+    let name = base.file_name()?.to_str()?;
+
+    let mut candidates = Vec::new();
+    if has_extension {
+        candidates.push(dir.join(format!("_{name}")));
+    } else {
+        for ext in extensions(syntax) {
+            if partials {
+                candidates.push(dir.join(format!("_{name}.{ext}")));
+            }
+            candidates.push(dir.join(format!("{name}.{ext}")));
+        }
+        for ext in extensions(syntax) {
+            if partials {
+                candidates.push(base.join(format!("_index.{ext}")));
+            }
+            candidates.push(base.join(format!("index.{ext}")));
+        }
+    }
+
+    for candidate in candidates {
+        if fs.exists(&candidate).await && !fs.is_directory(&candidate).await {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::CSS_IMPORT;
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+
+    fn stylesheets() -> MemoryFileSystem {
+        MemoryFileSystem::new([
+            ("/s/main.scss", ""),
+            ("/s/site.css", ""),
+            ("/s/_colors.scss", ""),
+            ("/s/colors.scss", ""),
+            ("/s/theme.css", ""),
+            ("/s/_grid.scss", ""),
+            ("/s/reset.css", ""),
+            ("/s/_reset.scss", ""),
+            ("/s/components/_index.scss", ""),
+            ("/s/components/index.scss", ""),
+            ("/s/forms.scss", ""),
+            ("/s/forms/_index.scss", ""),
+            ("/s/img/logo.png", ""),
+            ("/lib/vendor/_normalize.scss", ""),
+        ])
+    }
+
+    fn import(specifier: &str, kind: ImportKind) -> ImportStatement {
+        ImportStatement {
+            specifier: specifier.to_string(),
+            kind,
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_stylesheet_candidate_order() {
+        let fs = stylesheets();
+        let find = |base: &str, syntax| {
+            let fs = &fs;
+            let base = Path::new("/s").join(base);
+            async move { find_stylesheet(&base, syntax, fs).await }
+        };
+        let path = |p: &str| Some(PathBuf::from(p));
+
+        // Partials come before plain files, and Sass extensions before CSS
+        assert_eq!(find("colors", Syntax::Scss).await, path("/s/_colors.scss"));
+        assert_eq!(find("theme", Syntax::Scss).await, path("/s/theme.css"));
+        assert_eq!(find("grid.scss", Syntax::Scss).await, path("/s/_grid.scss"));
+        // `_index` comes before `index`, and files before directories
+        assert_eq!(
+            find("components", Syntax::Scss).await,
+            path("/s/components/_index.scss")
+        );
+        assert_eq!(find("forms", Syntax::Scss).await, path("/s/forms.scss"));
+        // Plain CSS knows neither partials nor Sass extensions
+        assert_eq!(find("reset", Syntax::Css).await, path("/s/reset.css"));
+        assert_eq!(find("grid", Syntax::Css).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_include_paths_and_assets() {
+        let fs = stylesheets();
+        let config = CssConfig {
+            include_paths: Some(vec![PathBuf::from("/lib")]),
+        };
+        let resolve = |specifier: &'static str, kind: ImportKind, resolver: CssResolver| {
+            let fs = &fs;
+            async move {
+                resolver
+                    .resolve(&import(specifier, kind), Path::new("/s/main.scss"), fs)
+                    .await
+                    .unwrap()
+                    .map(|r| (r.path.display().to_string(), r.is_asset))
+            }
+        };
+        let at_import = || ImportKind::Custom(CSS_IMPORT.to_string());
+
+        assert_eq!(
+            resolve(
+                "vendor/normalize",
+                at_import(),
+                CssResolver::new().with_config(&config)
+            )
+            .await,
+            Some(("/lib/vendor/_normalize.scss".to_string(), false))
+        );
+        assert_eq!(
+            resolve("vendor/normalize", at_import(), CssResolver::new()).await,
+            None
+        );
+        assert_eq!(
+            resolve(
+                "img/logo.png?v=2#icon",
+                ImportKind::Asset,
+                CssResolver::new()
+            )
+            .await,
+            Some(("/s/img/logo.png".to_string(), true))
+        );
+        for missing in [
+            "img/missing.png",
+            "/img/logo.png",
+            "https://cdn.example.com/a.png",
+        ] {
+            assert_eq!(
+                resolve(missing, ImportKind::Asset, CssResolver::new()).await,
+                None,
+                "{missing}"
+            );
+        }
+    }
+}
@@ -0,0 +1,72 @@
+mod parser;
+mod resolver;
+
+use crate::config::GoConfig;
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
+use anyhow::Result;
+use async_trait::async_trait;
+use parser::GoParser;
+use resolver::GoResolver;
+use std::path::Path;
+
+#[derive(Clone, Default)]
+pub struct GoAdapter {
+    parser: GoParser,
+    resolver: GoResolver,
+}
+
+impl GoAdapter {
+    pub fn new() -> Self {
+        Self {
+            parser: GoParser::new(),
+            resolver: GoResolver::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: &GoConfig) -> Self {
+        self.resolver = self.resolver.with_config(config);
+        self
+    }
+}
+
+#[async_trait]
+impl LanguageAdapter for GoAdapter {
+    fn name(&self) -> &str {
+        "Go"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["go"]
+    }
+
+    async fn parse_imports(
+        &self,
+        _file_path: &Path,
+        content: &str,
+        _context: &AnalysisContext,
+    ) -> Result<Vec<ImportStatement>> {
+        Ok(self.parser.parse(content))
+    }
+
+    async fn resolve_import(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Option<ResolvedImport>> {
+        Ok(self
+            .resolve_import_all(import, from_file, context)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    async fn resolve_import_all(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Vec<ResolvedImport>> {
+        self.resolver.resolve(import, from_file, &*context.fs).await
+    }
+}
@@ -0,0 +1,276 @@
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
+
+/// Kind of `import` declarations
+pub const GO_IMPORT: &str = "go";
+/// Kind of the `package` clause, which ties a file to the rest of its package
+pub const PACKAGE_CLAUSE: &str = "package";
+/// Specifier of the `package` clause, standing for the file's own package
+pub const OWN_PACKAGE: &str = ".";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    /// Contents of an interpreted or raw string literal
+    Str(String),
+}
+
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct GoParser;
+
+impl GoParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Finds the `package` clause and the `import` declarations following it,
+    /// in single and grouped form. Imports can only precede other top-level
+    /// declarations, so scanning stops at the first one.
+    pub fn parse(&self, content: &str) -> Vec<ImportStatement> {
+        let tokens = tokenize(content);
+        let lines: Vec<&str> = content.lines().collect();
+        let raw = |line: usize| lines.get(line - 1).map_or("", |l| l.trim()).to_string();
+
+        let mut imports = Vec::new();
+        let Some(start) = tokens
+            .iter()
+            .position(|t| t.token == Token::Ident("package".into()))
+        else {
+            return imports;
+        };
+        imports.push(ImportStatement {
+            specifier: OWN_PACKAGE.to_string(),
+            kind: ImportKind::Custom(PACKAGE_CLAUSE.to_string()),
+            line: tokens[start].line,
+            column: tokens[start].column,
+            raw: raw(tokens[start].line),
+            bindings: Vec::new(),
+        });
+
+        let mut i = start + 2;
+        while let Some(token) = tokens.get(i) {
+            match &token.token {
+                Token::Punct(';') => i += 1,
+                Token::Ident(word) if word == "import" => {
+                    let grouped = tokens.get(i + 1).map(|t| &t.token) == Some(&Token::Punct('('));
+                    // Tokens of the declaration, up to the closing parenthesis
+                    // or the single path
+                    let end = if grouped {
+                        tokens[i + 1..]
+                            .iter()
+                            .position(|t| t.token == Token::Punct(')'))
+                            .map_or(tokens.len(), |offset| i + 2 + offset)
+                    } else {
+                        tokens[i + 1..]
+                            .iter()
+                            .position(|t| matches!(t.token, Token::Str(_)))
+                            .map_or(tokens.len(), |offset| i + 2 + offset)
+                    };
+
+                    for j in i + 1..end {
+                        let Token::Str(path) = &tokens[j].token else {
+                            continue;
+                        };
+                        let alias = match &tokens[j - 1].token {
+                            Token::Ident(alias) if j - 1 > i => Some(alias.as_str()),
+                            Token::Punct('.') => Some("."),
+                            _ => None,
+                        };
+                        let spec = alias.map_or(j, |_| j - 1);
+                        imports.push(ImportStatement {
+                            specifier: path.clone(),
+                            kind: ImportKind::Custom(GO_IMPORT.to_string()),
+                            line: tokens[spec].line,
+                            column: tokens[spec].column,
+                            raw: if grouped {
+                                raw(tokens[spec].line)
+                            } else {
+                                raw(token.line)
+                            },
+                            bindings: bindings(path, alias),
+                        });
+                    }
+                    i = end;
+                }
+                _ => break,
+            }
+        }
+
+        imports
+    }
+}
+
+/// A package import binds its name, or the alias it is given. Blank and dot
+/// imports bind nothing that can be traced.
+fn bindings(path: &str, alias: Option<&str>) -> Vec<ImportBinding> {
+    let local = match alias {
+        Some("_" | ".") => return Vec::new(),
+        Some(alias) => alias,
+        None => path.rsplit('/').next().unwrap_or(path),
+    };
+    vec![ImportBinding::Namespace {
+        local: local.to_string(),
+        type_only: false,
+    }]
+}
+
+/// Splits Go source into identifiers, punctuation and string literals,
+/// skipping comments, rune literals and numbers
+fn tokenize(content: &str) -> Vec<Spanned> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut column = 0;
+    let mut i = 0;
+
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize, count: usize| {
+        for _ in 0..count {
+            if chars.get(*i) == Some(&'\n') {
+                *line += 1;
+                *column = 0;
+            } else {
+                *column += 1;
+            }
+            *i += 1;
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let (start_line, start_column) = (line, column);
+
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let len = chars[i..].iter().take_while(|&&c| c != '\n').count();
+            advance(&mut i, &mut line, &mut column, len);
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let len = (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                .map_or(chars.len() - i, |j| j + 2 - i);
+            advance(&mut i, &mut line, &mut column, len);
+        } else if c == '"' || c == '`' || c == '\'' {
+            let mut end = i + 1;
+            let mut value = String::new();
+            while end < chars.len() && chars[end] != c {
+                if c != '`' && chars[end] == '\\' {
+                    end += 1;
+                } else if c != '`' && chars[end] == '\n' {
+                    break;
+                }
+                if let Some(&ch) = chars.get(end) {
+                    value.push(ch);
+                }
+                end += 1;
+            }
+            let len = (end + 1).min(chars.len()) - i;
+            advance(&mut i, &mut line, &mut column, len);
+            if c != '\'' {
+                tokens.push(Spanned {
+                    token: Token::Str(value),
+                    line: start_line,
+                    column: start_column,
+                });
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|&&c| c.is_alphanumeric() || c == '_')
+                .count();
+            tokens.push(Spanned {
+                token: Token::Ident(chars[i..i + len].iter().collect()),
+                line: start_line,
+                column: start_column,
+            });
+            advance(&mut i, &mut line, &mut column, len);
+        } else if c.is_ascii_digit() {
+            let len = chars[i..]
+                .iter()
+                .take_while(|&&c| c.is_alphanumeric() || c == '.' || c == '_')
+                .count();
+            advance(&mut i, &mut line, &mut column, len);
+        } else {
+            if !c.is_whitespace() {
+                tokens.push(Spanned {
+                    token: Token::Punct(c),
+                    line: start_line,
+                    column: start_column,
+                });
+            }
+            advance(&mut i, &mut line, &mut column, 1);
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(imports: &[ImportStatement]) -> Vec<(&str, usize, usize)> {
+        imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.line, i.column))
+            .collect()
+    }
+
+    fn namespace(local: &str) -> Vec<ImportBinding> {
+        vec![ImportBinding::Namespace {
+            local: local.into(),
+            type_only: false,
+        }]
+    }
+
+    #[test]
+    fn test_package_clause_comes_first() {
+        let imports = GoParser::new()
+            .parse("// Package server has import \"fake\" in its docs\npackage server\n");
+
+        assert_eq!(summary(&imports), vec![(OWN_PACKAGE, 2, 0)]);
+        assert!(matches!(imports[0].kind, ImportKind::Custom(ref k) if k == PACKAGE_CLAUSE));
+        assert!(GoParser::new().parse("import \"fmt\"").is_empty());
+    }
+
+    #[test]
+    fn test_consecutive_single_and_grouped_imports() {
+        let content = "package main\n\nimport \"fmt\"\nimport cfg \"example.com/app/config\"\nimport (\n\t\"net/http\"\n\t/* block */ store `example.com/app/store`\n)\n";
+        let imports = GoParser::new().parse(content);
+
+        assert_eq!(
+            summary(&imports[1..]),
+            vec![
+                ("fmt", 3, 7),
+                ("example.com/app/config", 4, 7),
+                ("net/http", 6, 1),
+                ("example.com/app/store", 7, 13),
+            ]
+        );
+        assert_eq!(imports[1].raw, "import \"fmt\"");
+        assert_eq!(imports[4].raw, "/* block */ store `example.com/app/store`");
+    }
+
+    #[test]
+    fn test_import_aliases() {
+        let content = "package main\nimport (\n\tcfg \"example.com/app/config\"\n\t_ \"example.com/app/drivers\"\n\t. \"example.com/app/helpers\"\n\t\"example.com/app/v2/store\"\n)";
+        let imports = GoParser::new().parse(content);
+
+        assert_eq!(imports[1].bindings, namespace("cfg"));
+        assert!(imports[2].bindings.is_empty());
+        assert!(imports[3].bindings.is_empty());
+        // Without an alias the package binds the last path segment
+        assert_eq!(imports[4].bindings, namespace("store"));
+    }
+
+    #[test]
+    fn test_scan_stops_at_first_declaration() {
+        let content = "package main\n\nimport \"fmt\";\n\nfunc main() {\n\timport_ := \"x\"\n}\n\nimport \"late\"\n";
+        let imports = GoParser::new().parse(content);
+
+        assert_eq!(summary(&imports), vec![(OWN_PACKAGE, 1, 0), ("fmt", 3, 7)]);
+    }
+}
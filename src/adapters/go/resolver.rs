@@ -0,0 +1,619 @@
+use super::parser::OWN_PACKAGE;
+use crate::config::GoConfig;
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportStatement, ResolvedImport};
+use anyhow::Result;
+use dashmap::DashMap;
+use path_absolutize::Absolutize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Operating systems recognised in `_GOOS` file name suffixes
+const KNOWN_OS: &[&str] = &[
+    "aix",
+    "android",
+    "darwin",
+    "dragonfly",
+    "freebsd",
+    "hurd",
+    "illumos",
+    "ios",
+    "js",
+    "linux",
+    "nacl",
+    "netbsd",
+    "openbsd",
+    "plan9",
+    "solaris",
+    "wasip1",
+    "windows",
+    "zos",
+];
+
+/// Architectures recognised in `_GOARCH` file name suffixes
+const KNOWN_ARCH: &[&str] = &[
+    "386", "amd64", "arm", "arm64", "loong64", "mips", "mips64", "mips64le", "mipsle", "ppc64",
+    "ppc64le", "riscv64", "s390x", "wasm",
+];
+
+/// Operating systems that satisfy the `unix` build tag
+const UNIX_OS: &[&str] = &[
+    "aix",
+    "android",
+    "darwin",
+    "dragonfly",
+    "freebsd",
+    "hurd",
+    "illumos",
+    "ios",
+    "linux",
+    "netbsd",
+    "openbsd",
+    "solaris",
+];
+
+/// A module declared by a `go.mod` file
+#[derive(Debug)]
+struct GoModule {
+    root: PathBuf,
+    path: String,
+    /// `replace` directives pointing at local directories, as module path
+    /// and absolute directory
+    replacements: Vec<(String, PathBuf)>,
+}
+
+#[derive(Clone, Default)]
+pub struct GoResolver {
+    build_tags: Option<Vec<String>>,
+    /// Enclosing module by package directory
+    modules: Arc<DashMap<PathBuf, Option<Arc<GoModule>>>>,
+}
+
+impl GoResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, config: &GoConfig) -> Self {
+        self.build_tags = config.build_tags.clone();
+        self
+    }
+
+    /// Resolves an import path to its package, represented by the package's
+    /// primary file, and the `package` clause of the primary file to the rest
+    /// of the package. Any other file's clause leads to the primary file, so
+    /// a whole package is reached from any of its files.
+    ///
+    /// Only packages of the enclosing module and of modules replaced by local
+    /// directories are resolved; everything else is external.
+    pub async fn resolve(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Vec<ResolvedImport>> {
+        let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+
+        if import.specifier == OWN_PACKAGE {
+            let files = self.package_files(from_dir, fs).await;
+            let targets = match primary_file(from_dir, &files) {
+                Some(primary) if primary == from_file => files
+                    .iter()
+                    .filter(|file| file.as_path() != from_file)
+                    .cloned()
+                    .collect(),
+                Some(primary) => vec![primary.clone()],
+                None => Vec::new(),
+            };
+            return Ok(targets.into_iter().map(local).collect());
+        }
+
+        let Some(dir) = self.package_dir(&import.specifier, from_dir, fs).await else {
+            log::debug!(
+                "Treating '{}' from {} as external",
+                import.specifier,
+                from_file.display()
+            );
+            return Ok(Vec::new());
+        };
+
+        let files = self.package_files(&dir, fs).await;
+        match primary_file(&dir, &files) {
+            Some(primary) => Ok(vec![local(primary.clone())]),
+            None => {
+                log::debug!(
+                    "No Go files for '{}' in {}",
+                    import.specifier,
+                    dir.display()
+                );
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Directory of a package inside the enclosing module or a locally
+    /// replaced one
+    async fn package_dir(
+        &self,
+        import_path: &str,
+        from_dir: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<PathBuf> {
+        let module = self.module_for(from_dir, fs).await?;
+
+        let subpath = |prefix: &str| -> Option<String> {
+            let rest = import_path.strip_prefix(prefix)?;
+            (rest.is_empty() || rest.starts_with('/'))
+                .then(|| rest.trim_start_matches('/').to_string())
+        };
+
+        if let Some(rest) = subpath(&module.path) {
+            return Some(module.root.join(rest));
+        }
+
+        // The longest matching replacement wins, as nested modules can be
+        // replaced separately
+        module
+            .replacements
+            .iter()
+            .filter_map(|(path, dir)| Some((path.len(), dir.join(subpath(path)?))))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, dir)| dir)
+    }
+
+    async fn module_for(&self, dir: &Path, fs: &dyn FileSystemProvider) -> Option<Arc<GoModule>> {
+        if let Some(cached) = self.modules.get(dir) {
+            return cached.clone();
+        }
+
+        let mut module = None;
+        let mut current = Some(dir);
+        while let Some(candidate) = current {
+            if let Ok(content) = fs.read_file(&candidate.join("go.mod")).await {
+                module = parse_go_mod(&content, candidate).map(Arc::new);
+                break;
+            }
+            current = candidate.parent();
+        }
+
+        self.modules.insert(dir.to_path_buf(), module.clone());
+        module
+    }
+
+    /// The non-test `.go` files of the package in `dir`, sorted, filtered by
+    /// the configured build tags if any. When `dir` can't be listed, only the
+    /// files [`primary_file`] looks for by name are found.
+    async fn package_files(&self, dir: &Path, fs: &dyn FileSystemProvider) -> Vec<PathBuf> {
+        let entries = match fs.read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("Could not list Go package {}: {}", dir.display(), e);
+                let named = dir
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| dir.join(format!("{n}.go")));
+                let mut guessed = Vec::new();
+                for file in named.into_iter().chain([dir.join("main.go")]) {
+                    if fs.exists(&file).await {
+                        guessed.push(file);
+                    }
+                }
+                guessed
+            }
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let Some(name) = entry.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            // The go tool ignores files starting with `_` or `.`
+            if !name.ends_with(".go")
+                || name.ends_with("_test.go")
+                || name.starts_with(['_', '.'])
+                || fs.is_directory(&entry).await
+            {
+                continue;
+            }
+            if let Some(tags) = &self.build_tags {
+                if !matches_file_name(name, tags) {
+                    continue;
+                }
+                let content = fs.read_file(&entry).await.unwrap_or_default();
+                if !build_constraint(&content).is_none_or(|expr| evaluate(&expr, tags)) {
+                    log::debug!("Excluding {} by build constraint", entry.display());
+                    continue;
+                }
+            }
+            files.push(entry);
+        }
+        files
+    }
+}
+
+fn local(path: PathBuf) -> ResolvedImport {
+    ResolvedImport {
+        path,
+        is_local: true,
+        is_asset: false,
+    }
+}
+
+/// The file standing for a package: the one named after its directory, then
+/// `main.go`, then the first file
+fn primary_file<'a>(dir: &Path, files: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    let named = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| format!("{n}.go"));
+    let is_named = |file: &&PathBuf, name: &str| file.file_name().is_some_and(|n| n == name);
+
+    named
+        .and_then(|named| files.iter().find(|f| is_named(f, &named)))
+        .or_else(|| files.iter().find(|f| is_named(f, "main.go")))
+        .or_else(|| files.first())
+}
+
+fn parse_go_mod(content: &str, root: &Path) -> Option<GoModule> {
+    let mut path = None;
+    let mut replacements = Vec::new();
+    let mut in_replace_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_replace_block {
+            if line == ")" {
+                in_replace_block = false;
+            } else if let Some(replacement) = replacement(line, root) {
+                replacements.push(replacement);
+            }
+        } else if let Some(rest) = line.strip_prefix("module ") {
+            path = Some(unquote(rest.trim()).to_string());
+        } else if let Some(rest) = line.strip_prefix("replace") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_replace_block = true;
+            } else if let Some(replacement) = replacement(rest, root) {
+                replacements.push(replacement);
+            }
+        }
+    }
+
+    Some(GoModule {
+        root: root.to_path_buf(),
+        path: path?,
+        replacements,
+    })
+}
+
+/// `old [version] => new [version]`, kept only when `new` is a local
+/// directory
+fn replacement(directive: &str, root: &Path) -> Option<(String, PathBuf)> {
+    let (old, new) = directive.split_once("=>")?;
+    let old = unquote(old.split_whitespace().next()?);
+    let new = unquote(new.split_whitespace().next()?);
+    if !(new.starts_with("./") || new.starts_with("../") || Path::new(new).is_absolute()) {
+        return None;
+    }
+    let dir = root.join(new).absolutize().ok()?.to_path_buf();
+    Some((old.to_string(), dir))
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '`')
+}
+
+/// Whether `_GOOS`, `_GOARCH` and `_GOOS_GOARCH` file name suffixes match
+fn matches_file_name(name: &str, tags: &[String]) -> bool {
+    let stem = name.trim_end_matches(".go");
+    let parts: Vec<&str> = stem.split('_').skip(1).collect();
+    let has = |tag: &str| tags.iter().any(|t| t == tag);
+
+    match parts.as_slice() {
+        [.., os, arch] if KNOWN_OS.contains(os) && KNOWN_ARCH.contains(arch) => {
+            has(os) && has(arch)
+        }
+        [.., last] if KNOWN_ARCH.contains(last) => has(last),
+        [.., last] if KNOWN_OS.contains(last) => has(last),
+        _ => true,
+    }
+}
+
+/// The expression of a `//go:build` line, which must precede the package
+/// clause
+fn build_constraint(content: &str) -> Option<String> {
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(expr) = line.strip_prefix("//go:build") {
+            return Some(expr.trim().to_string());
+        }
+        if !line.is_empty() && !line.starts_with("//") {
+            return None;
+        }
+    }
+    None
+}
+
+/// Evaluates a build constraint expression of tags combined with `!`, `&&`,
+/// `||` and parentheses. Malformed expressions count as satisfied.
+fn evaluate(expr: &str, tags: &[String]) -> bool {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|&&c| c.is_alphanumeric() || c == '_' || c == '.')
+                .count();
+            tokens.push(chars[i..i + len].iter().collect::<String>());
+            i += len;
+        } else if (c == '&' || c == '|') && chars.get(i + 1) == Some(&c) {
+            tokens.push(format!("{c}{c}"));
+            i += 2;
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+
+    let mut parser = ConstraintParser {
+        tokens: &tokens,
+        pos: 0,
+        tags,
+    };
+    match parser.or() {
+        Some(value) if parser.pos == tokens.len() => value,
+        _ => true,
+    }
+}
+
+struct ConstraintParser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+    tags: &'a [String],
+}
+
+impl ConstraintParser<'_> {
+    fn or(&mut self) -> Option<bool> {
+        let mut value = self.and()?;
+        while self.eat("||") {
+            value |= self.and()?;
+        }
+        Some(value)
+    }
+
+    fn and(&mut self) -> Option<bool> {
+        let mut value = self.not()?;
+        while self.eat("&&") {
+            value &= self.not()?;
+        }
+        Some(value)
+    }
+
+    fn not(&mut self) -> Option<bool> {
+        if self.eat("!") {
+            return Some(!self.not()?);
+        }
+        if self.eat("(") {
+            let value = self.or()?;
+            return self.eat(")").then_some(value);
+        }
+        let tag = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(self.satisfied(tag))
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let matches = self.tokens.get(self.pos).is_some_and(|t| t == token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    /// Release tags like `go1.21` always hold, and `unix` holds for any Unix
+    /// operating system
+    fn satisfied(&self, tag: &str) -> bool {
+        tag.starts_with("go1.")
+            || self.tags.iter().any(|t| t == tag)
+            || (tag == "unix" && self.tags.iter().any(|t| UNIX_OS.contains(&t.as_str())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::GO_IMPORT;
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+    use crate::core::language::ImportKind;
+
+    fn module_fs() -> MemoryFileSystem {
+        MemoryFileSystem::new([
+            (
+                "/work/app/go.mod",
+                "module example.com/app\n\nreplace example.com/lib => ../lib\nreplace example.com/lib/ext => ../ext\n",
+            ),
+            ("/work/app/server/server.go", "package server"),
+            ("/work/app/server/handler.go", "package server"),
+            ("/work/app/server/handler_test.go", "package server"),
+            ("/work/app/server/_scratch.go", "package server"),
+            ("/work/lib/util/util.go", "package util"),
+            ("/work/ext/sub/sub.go", "package sub"),
+        ])
+    }
+
+    fn import(specifier: &str) -> ImportStatement {
+        ImportStatement {
+            specifier: specifier.to_string(),
+            kind: ImportKind::Custom(GO_IMPORT.to_string()),
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_package_dir_prefers_longest_replacement() {
+        let fs = module_fs();
+        let resolver = GoResolver::new();
+        let from = Path::new("/work/app/server");
+
+        let cases = [
+            ("example.com/app/server", Some("/work/app/server")),
+            ("example.com/lib/util", Some("/work/lib/util")),
+            ("example.com/lib/ext/sub", Some("/work/ext/sub")),
+            ("example.com/lib", Some("/work/lib")),
+            ("example.com/library", None),
+            ("github.com/pkg/errors", None),
+        ];
+        for (import_path, expected) in cases {
+            assert_eq!(
+                resolver.package_dir(import_path, from, &fs).await,
+                expected.map(PathBuf::from),
+                "{import_path}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_package_clause_links_files_through_primary_file() {
+        let fs = module_fs();
+        let resolver = GoResolver::new();
+        let resolve = |from: &'static str| {
+            let resolver = resolver.clone();
+            let fs = &fs;
+            async move {
+                resolver
+                    .resolve(&import(OWN_PACKAGE), Path::new(from), fs)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| r.path)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Test files and files starting with `_` are not part of the package
+        assert_eq!(
+            resolve("/work/app/server/server.go").await,
+            vec![PathBuf::from("/work/app/server/handler.go")]
+        );
+        assert_eq!(
+            resolve("/work/app/server/handler.go").await,
+            vec![PathBuf::from("/work/app/server/server.go")]
+        );
+
+        let imported = resolver
+            .resolve(
+                &import("example.com/lib/ext/sub"),
+                Path::new("/work/app/server/server.go"),
+                &fs,
+            )
+            .await
+            .unwrap();
+        assert_eq!(imported[0].path, PathBuf::from("/work/ext/sub/sub.go"));
+    }
+
+    /// A file system that can't list directories, like one written before
+    /// `read_dir` existed
+    struct Unlisted(MemoryFileSystem);
+
+    #[async_trait::async_trait]
+    impl FileSystemProvider for Unlisted {
+        async fn read_file(&self, path: &Path) -> Result<String> {
+            self.0.read_file(path).await
+        }
+
+        async fn exists(&self, path: &Path) -> bool {
+            self.0.exists(path).await
+        }
+
+        async fn is_directory(&self, path: &Path) -> bool {
+            self.0.is_directory(path).await
+        }
+
+        async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+            self.0.canonicalize(path).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unlisted_packages_fall_back_to_primary_file_names() {
+        let fs = Unlisted(module_fs());
+        let resolver = GoResolver::new();
+        let from = Path::new("/work/app/server/server.go");
+
+        let imported = resolver
+            .resolve(&import("example.com/lib/util"), from, &fs)
+            .await
+            .unwrap();
+        assert_eq!(imported[0].path, PathBuf::from("/work/lib/util/util.go"));
+        // Other files of the package can't be found without a listing
+        let own = resolver
+            .resolve(&import(OWN_PACKAGE), from, &fs)
+            .await
+            .unwrap();
+        assert!(own.is_empty());
+    }
+
+    #[test]
+    fn test_primary_file_order() {
+        let files = |names: &[&str]| -> Vec<PathBuf> {
+            names
+                .iter()
+                .map(|n| Path::new("/pkg/api").join(n))
+                .collect()
+        };
+        let dir = Path::new("/pkg/api");
+
+        let named = files(&["a.go", "api.go", "main.go"]);
+        assert_eq!(primary_file(dir, &named), Some(&named[1]));
+        let main = files(&["a.go", "main.go"]);
+        assert_eq!(primary_file(dir, &main), Some(&main[1]));
+        let first = files(&["b.go", "c.go"]);
+        assert_eq!(primary_file(dir, &first), Some(&first[0]));
+        assert_eq!(primary_file(dir, &[]), None);
+    }
+
+    #[test]
+    fn test_go_mod_and_build_constraints() {
+        let root = Path::new("/work/app");
+        let module = parse_go_mod(
+            r#"module example.com/app // main module
+
+go 1.22
+
+require example.com/shared v1.2.0
+
+replace example.com/shared => ../shared
+replace (
+	example.com/remote v1.0.0 => example.com/fork v1.0.1
+	"example.com/tools" => ./tools
+)
+"#,
+            root,
+        )
+        .unwrap();
+        assert_eq!(module.path, "example.com/app");
+        assert_eq!(
+            module.replacements,
+            vec![
+                ("example.com/shared".into(), PathBuf::from("/work/shared")),
+                ("example.com/tools".into(), PathBuf::from("/work/app/tools")),
+            ]
+        );
+
+        let tags = vec!["linux".to_string(), "amd64".to_string()];
+        assert!(evaluate("linux && (amd64 || arm64)", &tags));
+        assert!(!evaluate("!linux || windows", &tags));
+        assert!(evaluate("unix && go1.21", &tags));
+        assert!(matches_file_name("poll_linux_amd64.go", &tags));
+        assert!(!matches_file_name("poll_windows.go", &tags));
+        assert!(matches_file_name("server.go", &tags));
+    }
+}
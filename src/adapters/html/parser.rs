@@ -0,0 +1,338 @@
+use crate::adapters::line_index::LineIndex;
+use crate::core::language::{ImportKind, ImportStatement};
+
+/// Kind of classic `<script src>` references
+pub const HTML_SCRIPT: &str = "script";
+/// Kind of `<link rel="stylesheet">` references
+pub const HTML_STYLESHEET: &str = "stylesheet";
+/// Kind of `<template>` includes of other HTML files
+pub const HTML_INCLUDE: &str = "include";
+
+/// `<link rel>` values whose `href` is an asset
+const ASSET_RELS: &[&str] = &["icon", "apple-touch-icon", "manifest", "mask-icon"];
+
+/// Script types that hold JavaScript rather than data or templates
+const SCRIPT_TYPES: &[&str] = &["", "module", "text/javascript", "application/javascript"];
+
+struct Tag {
+    name: String,
+    attributes: Vec<(String, String)>,
+    start: usize,
+    end: usize,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct HtmlParser;
+
+impl HtmlParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Finds the local references of a page: `<script src>`, stylesheet and
+    /// module preload `<link>`s, icons, `<img>`/`<source>` `src` and `srcset`,
+    /// and `<template src>` or `<template data-include>` includes. Comments
+    /// and the contents of `<script>` and `<style>` elements are skipped.
+    pub fn parse(&self, content: &str) -> Vec<ImportStatement> {
+        let chars: Vec<char> = content.chars().collect();
+        let lines = LineIndex::new(&chars);
+
+        let mut imports = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] != '<' {
+                i += 1;
+                continue;
+            }
+            if starts_with(&chars, i, "<!--") {
+                i = find(&chars, i + 4, "-->").map_or(chars.len(), |end| end + 3);
+                continue;
+            }
+            let Some(tag) = tag_at(&chars, i) else {
+                i += 1;
+                continue;
+            };
+            i = tag.end;
+
+            let (line, column) = lines.position(tag.start);
+            let raw: String = chars[tag.start..tag.end].iter().collect();
+            let raw = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+            let mut push = |specifier: &str, kind: ImportKind| {
+                let specifier = specifier.trim();
+                if !specifier.is_empty() {
+                    imports.push(ImportStatement {
+                        specifier: specifier.to_string(),
+                        kind,
+                        line,
+                        column,
+                        raw: raw.clone(),
+                        bindings: Vec::new(),
+                    });
+                }
+            };
+
+            match tag.name.as_str() {
+                "script" => {
+                    let script_type = tag.attribute("type").unwrap_or("").to_lowercase();
+                    if let Some(src) = tag.attribute("src") {
+                        if SCRIPT_TYPES.contains(&script_type.as_str()) {
+                            let kind = if script_type == "module" {
+                                ImportKind::EsModule
+                            } else {
+                                ImportKind::Custom(HTML_SCRIPT.to_string())
+                            };
+                            push(src, kind);
+                        }
+                    }
+                    i = skip_raw_text(&chars, i, "script");
+                }
+                "style" => i = skip_raw_text(&chars, i, "style"),
+                "link" => {
+                    let (Some(rel), Some(href)) = (tag.attribute("rel"), tag.attribute("href"))
+                    else {
+                        continue;
+                    };
+                    let rel = rel.to_lowercase();
+                    let rels: Vec<&str> = rel.split_whitespace().collect();
+                    if rels.contains(&"stylesheet") {
+                        push(href, ImportKind::Custom(HTML_STYLESHEET.to_string()));
+                    } else if rels.contains(&"modulepreload") {
+                        push(href, ImportKind::EsModule);
+                    } else if rels.iter().any(|r| ASSET_RELS.contains(r)) {
+                        push(href, ImportKind::Asset);
+                    }
+                }
+                "img" | "source" => {
+                    if let Some(src) = tag.attribute("src") {
+                        push(src, ImportKind::Asset);
+                    }
+                    if let Some(srcset) = tag.attribute("srcset") {
+                        // Candidates are a URL followed by an optional descriptor
+                        for candidate in srcset.split(',') {
+                            if let Some(url) = candidate.split_whitespace().next() {
+                                push(url, ImportKind::Asset);
+                            }
+                        }
+                    }
+                }
+                "template" => {
+                    if let Some(src) = tag.attribute("src").or(tag.attribute("data-include")) {
+                        push(src, ImportKind::Custom(HTML_INCLUDE.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        imports
+    }
+}
+
+fn starts_with(chars: &[char], at: usize, text: &str) -> bool {
+    text.chars()
+        .enumerate()
+        .all(|(offset, c)| chars.get(at + offset) == Some(&c))
+}
+
+fn find(chars: &[char], from: usize, text: &str) -> Option<usize> {
+    (from..chars.len()).find(|&j| starts_with(chars, j, text))
+}
+
+/// Index of the end of the closing tag of a raw text element, which can't
+/// contain other elements
+fn skip_raw_text(chars: &[char], from: usize, name: &str) -> usize {
+    let mut j = from;
+    while let Some(start) = find(chars, j, "</") {
+        let tag: String = chars[start + 2..(start + 2 + name.len()).min(chars.len())]
+            .iter()
+            .collect();
+        if tag.eq_ignore_ascii_case(name) {
+            return find(chars, start, ">").map_or(chars.len(), |end| end + 1);
+        }
+        j = start + 2;
+    }
+    chars.len()
+}
+
+/// The start tag at `start` with its lowercased name and attributes
+fn tag_at(chars: &[char], start: usize) -> Option<Tag> {
+    let mut j = start + 1;
+    if !chars.get(j)?.is_ascii_alphabetic() {
+        return None;
+    }
+    let name_len = chars[j..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '-')
+        .count();
+    let name = chars[j..j + name_len]
+        .iter()
+        .collect::<String>()
+        .to_lowercase();
+    j += name_len;
+
+    let mut attributes = Vec::new();
+    loop {
+        while chars.get(j).is_some_and(|c| c.is_whitespace() || *c == '/') {
+            j += 1;
+        }
+        match chars.get(j) {
+            None => return None,
+            Some('>') => break,
+            _ => {}
+        }
+
+        let name_len = chars[j..]
+            .iter()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, '=' | '>' | '/'))
+            .count()
+            .max(1);
+        let attr_name = chars[j..j + name_len]
+            .iter()
+            .collect::<String>()
+            .to_lowercase();
+        j += name_len;
+        while chars.get(j).is_some_and(|c| c.is_whitespace()) {
+            j += 1;
+        }
+
+        let mut value = String::new();
+        if chars.get(j) == Some(&'=') {
+            j += 1;
+            while chars.get(j).is_some_and(|c| c.is_whitespace()) {
+                j += 1;
+            }
+            match chars.get(j) {
+                Some(&quote @ ('"' | '\'')) => {
+                    let end = (j + 1..chars.len()).find(|&k| chars[k] == quote)?;
+                    value = chars[j + 1..end].iter().collect();
+                    j = end + 1;
+                }
+                _ => {
+                    let len = chars[j..]
+                        .iter()
+                        .take_while(|c| !c.is_whitespace() && **c != '>')
+                        .count();
+                    value = chars[j..j + len].iter().collect();
+                    j += len;
+                }
+            }
+        }
+        attributes.push((attr_name, value.replace("&amp;", "&")));
+    }
+
+    Some(Tag {
+        name,
+        attributes,
+        start,
+        end: j + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Specifier and kind of every reference, with custom kinds by name
+    fn references(content: &str) -> Vec<(String, String)> {
+        HtmlParser::new()
+            .parse(content)
+            .into_iter()
+            .map(|i| {
+                let kind = match i.kind {
+                    ImportKind::Custom(kind) => kind,
+                    other => format!("{:?}", other),
+                };
+                (i.specifier, kind)
+            })
+            .collect()
+    }
+
+    fn reference(specifier: &str, kind: &str) -> (String, String) {
+        (specifier.to_string(), kind.to_string())
+    }
+
+    #[test]
+    fn test_script_types() {
+        let content = r#"<script type="module" src="/src/main.ts"></script>
+<SCRIPT SRC='legacy.js' defer></SCRIPT>
+<script type="text/javascript" src="analytics.js"></script>
+<script type="text/x-template" src="./tpl.html"></script>
+<script type="application/json" src="data.json"></script>"#;
+
+        assert_eq!(
+            references(content),
+            vec![
+                reference("/src/main.ts", "EsModule"),
+                reference("legacy.js", HTML_SCRIPT),
+                reference("analytics.js", HTML_SCRIPT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_rels() {
+        let content = r#"<link rel="alternate stylesheet" href="./dark.css">
+<link rel="modulepreload" href="/src/vendor.ts">
+<link rel="icon" href="/favicon.svg"><link rel="manifest" href="app.webmanifest">
+<link rel="preconnect" href="https://fonts.example.com">
+<link rel="stylesheet">"#;
+
+        assert_eq!(
+            references(content),
+            vec![
+                reference("./dark.css", HTML_STYLESHEET),
+                reference("/src/vendor.ts", "EsModule"),
+                reference("/favicon.svg", "Asset"),
+                reference("app.webmanifest", "Asset"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comments_and_raw_text_are_skipped() {
+        let content = r#"<!-- <script src="./old.js"></script> -->
+<script>
+  document.write('<script src="./inline.js"></scr' + 'ipt>');
+</script>
+<style>.a::after { content: '<img src="x.png">'; }</style>
+<img src="kept.png">"#;
+
+        assert_eq!(references(content), vec![reference("kept.png", "Asset")]);
+    }
+
+    #[test]
+    fn test_images_and_includes() {
+        let content = "<picture>\n  <source srcset=\"hero.webp 1x, hero@2x.webp 2x\">\n  <img\n    src=hero.png?a=1&amp;b=2 alt=\"Hero\">\n</picture>\n<template src=\"partials/header.html\"></template>\n<template data-include=\"partials/footer.html\"></template>";
+        let imports = HtmlParser::new().parse(content);
+        let summary: Vec<(&str, usize, usize)> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.line, i.column))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("hero.webp", 2, 2),
+                ("hero@2x.webp", 2, 2),
+                ("hero.png?a=1&b=2", 3, 2),
+                ("partials/header.html", 6, 0),
+                ("partials/footer.html", 7, 0),
+            ]
+        );
+        assert!(matches!(imports[3].kind, ImportKind::Custom(ref k) if k == HTML_INCLUDE));
+        assert_eq!(
+            imports[2].raw,
+            "<img src=hero.png?a=1&amp;b=2 alt=\"Hero\">"
+        );
+    }
+}
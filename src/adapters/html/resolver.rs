@@ -0,0 +1,131 @@
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportKind, ImportStatement, ResolvedImport};
+use anyhow::Result;
+use path_absolutize::Absolutize;
+use std::path::Path;
+
+#[derive(Clone, Copy, Default)]
+pub struct HtmlResolver;
+
+impl HtmlResolver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Resolves a reference against the page's directory. Root-relative paths
+    /// like `/src/main.ts` are taken from the page's directory too, as dev
+    /// servers serve the directory holding `index.html` as the root. URLs with
+    /// a scheme or host are external.
+    pub async fn resolve(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<ResolvedImport>> {
+        let specifier = import.specifier.as_str();
+        if specifier.starts_with("//") || specifier.starts_with('#') || has_scheme(specifier) {
+            log::debug!("Skipping external reference '{}'", specifier);
+            return Ok(None);
+        }
+
+        let path = specifier.split(['?', '#']).next().unwrap_or(specifier);
+        let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+        let target = from_dir.join(path.trim_start_matches('/'));
+
+        if !fs.exists(&target).await || fs.is_directory(&target).await {
+            log::debug!(
+                "Reference '{}' from {} not found",
+                specifier,
+                from_file.display()
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(ResolvedImport {
+            path: target.absolutize()?.to_path_buf(),
+            is_local: true,
+            is_asset: matches!(import.kind, ImportKind::Asset),
+        }))
+    }
+}
+
+/// `https:`, `data:`, `mailto:` and the like
+fn has_scheme(specifier: &str) -> bool {
+    specifier.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+    use std::path::PathBuf;
+
+    fn reference(specifier: &str, kind: ImportKind) -> ImportStatement {
+        ImportStatement {
+            specifier: specifier.to_string(),
+            kind,
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_root_relative_paths_resolve_from_page_directory() {
+        let fs = MemoryFileSystem::new([
+            ("/site/index.html", ""),
+            ("/site/src/main.ts", ""),
+            ("/site/img/logo.png", ""),
+        ]);
+        let from = Path::new("/site/index.html");
+
+        let script = HtmlResolver::new()
+            .resolve(
+                &reference("/src/main.ts?v=1", ImportKind::EsModule),
+                from,
+                &fs,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(script.path, PathBuf::from("/site/src/main.ts"));
+        assert!(!script.is_asset);
+
+        let image = HtmlResolver::new()
+            .resolve(&reference("img/logo.png", ImportKind::Asset), from, &fs)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(image.path, PathBuf::from("/site/img/logo.png"));
+        assert!(image.is_asset);
+
+        for missing in ["/src", "/src/other.ts", "//cdn.example.com/lib.js"] {
+            let resolved = HtmlResolver::new()
+                .resolve(&reference(missing, ImportKind::EsModule), from, &fs)
+                .await
+                .unwrap();
+            assert!(resolved.is_none(), "{missing} should not resolve");
+        }
+    }
+
+    #[test]
+    fn test_has_scheme() {
+        for url in [
+            "https://example.com/a.js",
+            "data:image/png;base64,AA",
+            "mailto:a@b.c",
+            "git+ssh:x",
+        ] {
+            assert!(has_scheme(url), "{url}");
+        }
+        for path in ["src/main.ts", "/src/main.ts", "./a:b.js", ":root.css"] {
+            assert!(!has_scheme(path), "{path}");
+        }
+    }
+}
@@ -0,0 +1,630 @@
+use super::parser::{parse_module, syntax_for_file};
+use crate::core::fs::FileSystemProvider;
+use dashmap::DashMap;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use swc_ecma_ast::{
+    ArrayLit, Callee, Expr, KeyValueProp, Lit, MemberProp, MetaPropKind, ObjectLit, Prop, PropName,
+    PropOrSpread,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Bundler and test runner configs that may define import aliases, in the
+/// order their aliases are tried
+pub const ALIAS_CONFIG_FILES: &[&str] = &[
+    "vite.config.ts",
+    "vite.config.mts",
+    "vite.config.js",
+    "vite.config.mjs",
+    "vite.config.cjs",
+    "webpack.config.js",
+    "webpack.config.ts",
+    "webpack.config.cjs",
+    "webpack.config.mjs",
+    "babel.config.js",
+    "babel.config.cjs",
+    "babel.config.mjs",
+    "babel.config.json",
+    ".babelrc",
+    ".babelrc.js",
+    ".babelrc.json",
+    "jest.config.js",
+    "jest.config.ts",
+    "jest.config.cjs",
+    "jest.config.mjs",
+    "jest.config.json",
+];
+
+const MODULE_RESOLVER_PLUGINS: &[&str] = &["module-resolver", "babel-plugin-module-resolver"];
+
+#[derive(Debug)]
+enum AliasMatcher {
+    /// Matches the key itself and anything below it (`@` matches `@/x`)
+    Prefix(String),
+    /// Webpack's `key$` form
+    Exact(String),
+    /// Replaces the matched part of the specifier (Vite `find` regexes)
+    Replace(Regex),
+    /// Maps the whole specifier, with `$1` style captures (Jest, babel)
+    Map(Regex),
+}
+
+#[derive(Debug)]
+struct AliasRule {
+    matcher: AliasMatcher,
+    /// Absolute path templates
+    replacements: Vec<String>,
+}
+
+impl AliasRule {
+    fn apply(&self, specifier: &str) -> Option<Vec<PathBuf>> {
+        let mapped: Vec<String> = match &self.matcher {
+            AliasMatcher::Prefix(key) => {
+                let rest = if specifier == key.trim_end_matches('/') {
+                    ""
+                } else if key.ends_with('/') {
+                    specifier.strip_prefix(key.as_str())?
+                } else {
+                    specifier.strip_prefix(key.as_str())?.strip_prefix('/')?
+                };
+                self.replacements
+                    .iter()
+                    .map(|r| {
+                        if rest.is_empty() {
+                            r.clone()
+                        } else {
+                            format!("{}/{}", r.trim_end_matches('/'), rest)
+                        }
+                    })
+                    .collect()
+            }
+            AliasMatcher::Exact(key) if key == specifier => self.replacements.clone(),
+            AliasMatcher::Exact(_) => return None,
+            AliasMatcher::Replace(regex) => {
+                if !regex.is_match(specifier) {
+                    return None;
+                }
+                self.replacements
+                    .iter()
+                    .map(|r| regex.replace(specifier, r.as_str()).into_owned())
+                    .collect()
+            }
+            AliasMatcher::Map(regex) => {
+                let captures = regex.captures(specifier)?;
+                self.replacements
+                    .iter()
+                    .map(|r| {
+                        let mut expanded = String::new();
+                        captures.expand(r, &mut expanded);
+                        expanded
+                    })
+                    .collect()
+            }
+        };
+
+        Some(mapped.into_iter().map(PathBuf::from).collect())
+    }
+}
+
+/// Aliases collected from the configs of one project directory
+#[derive(Debug, Default)]
+pub struct AliasConfig {
+    rules: Vec<AliasRule>,
+}
+
+impl AliasConfig {
+    /// Candidate paths for `specifier` from the first matching alias
+    pub fn resolve_alias(&self, specifier: &str) -> Option<Vec<PathBuf>> {
+        self.rules.iter().find_map(|rule| rule.apply(specifier))
+    }
+
+    /// Extracts literal alias maps from a Vite/webpack/babel/Jest config or a
+    /// `package.json` with `jest`/`babel` fields
+    fn extract(&mut self, path: &Path, content: &str) {
+        let dir = path.parent().unwrap_or_else(|| Path::new("/"));
+
+        // JSON configs are parsed as a parenthesized object expression
+        let is_json = path.extension().is_some_and(|e| e == "json")
+            || path.file_name().is_some_and(|n| n == ".babelrc");
+        let source = if is_json {
+            format!("({})", content)
+        } else {
+            content.to_string()
+        };
+
+        let module = match parse_module(path, &source, syntax_for_file(path)) {
+            Ok((module, _)) => module,
+            Err(e) => {
+                log::warn!("Could not read aliases from {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        let mut visitor = AliasVisitor {
+            dir,
+            rules: Vec::new(),
+        };
+        module.visit_with(&mut visitor);
+
+        if !visitor.rules.is_empty() {
+            log::debug!(
+                "Found {} aliases in {}",
+                visitor.rules.len(),
+                path.display()
+            );
+        }
+        self.rules.extend(visitor.rules);
+    }
+}
+
+/// Finds the alias configs that apply to a file. Configs are looked up in the
+/// nearest directory above the file that has any of them.
+#[derive(Default)]
+pub struct AliasResolver {
+    configs: DashMap<PathBuf, Option<Arc<AliasConfig>>>,
+}
+
+impl AliasResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn config_for(
+        &self,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<Arc<AliasConfig>> {
+        let mut visited = Vec::new();
+        let mut current = from_file.parent();
+        let mut found = None;
+
+        while let Some(dir) = current {
+            if let Some(cached) = self.configs.get(dir) {
+                found = cached.clone();
+                break;
+            }
+            visited.push(dir.to_path_buf());
+            if dir.file_name().is_some_and(|n| n == "node_modules") {
+                break;
+            }
+            if let Some(config) = load_dir(dir, fs).await {
+                found = Some(Arc::new(config));
+                break;
+            }
+            current = dir.parent();
+        }
+
+        for dir in visited {
+            self.configs.insert(dir, found.clone());
+        }
+        found
+    }
+}
+
+/// Aliases from every config in `dir`, or `None` if it has no configs
+async fn load_dir(dir: &Path, fs: &dyn FileSystemProvider) -> Option<AliasConfig> {
+    let mut config = AliasConfig::default();
+    let mut has_config = false;
+
+    for name in ALIAS_CONFIG_FILES {
+        let path = dir.join(name);
+        if !fs.exists(&path).await {
+            continue;
+        }
+        if let Ok(content) = fs.read_file(&path).await {
+            has_config = true;
+            config.extract(&path, &content);
+        }
+    }
+
+    // Only top-level `jest` and `babel` fields count: a package.json that
+    // merely depends on jest must not hide configs further up
+    let package_json = dir.join("package.json");
+    if fs.exists(&package_json).await {
+        if let Ok(content) = fs.read_file(&package_json).await {
+            let has_fields = serde_json::from_str::<serde_json::Value>(&content)
+                .is_ok_and(|json| json.get("jest").is_some() || json.get("babel").is_some());
+            if has_fields {
+                has_config = true;
+                config.extract(&package_json, &content);
+            }
+        }
+    }
+
+    has_config.then_some(config)
+}
+
+struct AliasVisitor<'a> {
+    dir: &'a Path,
+    rules: Vec<AliasRule>,
+}
+
+impl AliasVisitor<'_> {
+    /// Vite/webpack `resolve.alias`, either an object or an array of
+    /// `{ find, replacement }` entries
+    fn add_resolve_aliases(&mut self, alias: &Expr) {
+        match unwrap_expr(alias) {
+            Expr::Object(object) => {
+                for (key, value) in key_values(object) {
+                    let Some(replacements) = self.replacements(value) else {
+                        continue;
+                    };
+                    let matcher = match key.strip_suffix('$') {
+                        Some(exact) => AliasMatcher::Exact(exact.to_string()),
+                        None => AliasMatcher::Prefix(key),
+                    };
+                    self.rules.push(AliasRule {
+                        matcher,
+                        replacements,
+                    });
+                }
+            }
+            Expr::Array(array) => {
+                for entry in array.elems.iter().flatten() {
+                    let Expr::Object(object) = unwrap_expr(&entry.expr) else {
+                        continue;
+                    };
+                    let (mut find, mut replacement) = (None, None);
+                    for prop in &object.props {
+                        if let PropOrSpread::Prop(prop) = prop {
+                            if let Prop::KeyValue(kv) = &**prop {
+                                match prop_name(&kv.key).as_deref() {
+                                    Some("find") => find = Some(&*kv.value),
+                                    Some("replacement") => replacement = Some(&*kv.value),
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+
+                    let (Some(find), Some(replacement)) = (find, replacement) else {
+                        continue;
+                    };
+                    let Some(replacements) = self.replacements(replacement) else {
+                        continue;
+                    };
+                    let matcher = match unwrap_expr(find) {
+                        Expr::Lit(Lit::Regex(regex)) => match Regex::new(&regex.exp) {
+                            Ok(regex) => AliasMatcher::Replace(regex),
+                            Err(_) => continue,
+                        },
+                        expr => match eval_string(expr, self.dir) {
+                            Some(find) => AliasMatcher::Prefix(find),
+                            None => continue,
+                        },
+                    };
+                    self.rules.push(AliasRule {
+                        matcher,
+                        replacements: replacements.into_iter().map(js_replacement).collect(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Jest `moduleNameMapper`: regex keys mapped to `<rootDir>` paths
+    fn add_module_name_mapper(&mut self, mapper: &Expr) {
+        let Expr::Object(object) = unwrap_expr(mapper) else {
+            return;
+        };
+        for (key, value) in key_values(object) {
+            let (Ok(regex), Some(replacements)) = (Regex::new(&key), self.replacements(value))
+            else {
+                continue;
+            };
+            self.rules.push(AliasRule {
+                matcher: AliasMatcher::Map(regex),
+                replacements: replacements.into_iter().map(js_replacement).collect(),
+            });
+        }
+    }
+
+    /// Babel `module-resolver` plugin options
+    fn add_module_resolver(&mut self, options: &ObjectLit) {
+        for (key, value) in key_values(options) {
+            if key != "alias" {
+                continue;
+            }
+            let Expr::Object(alias) = unwrap_expr(value) else {
+                continue;
+            };
+            for (key, value) in key_values(alias) {
+                let Some(replacements) = self.replacements(value) else {
+                    continue;
+                };
+                let matcher = if key.starts_with('^') {
+                    match Regex::new(&key) {
+                        Ok(regex) => AliasMatcher::Map(regex),
+                        Err(_) => continue,
+                    }
+                } else {
+                    AliasMatcher::Prefix(key)
+                };
+                self.rules.push(AliasRule {
+                    matcher,
+                    replacements: replacements
+                        .into_iter()
+                        .map(|r| babel_replacement(&r))
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    /// Absolute replacement paths for an alias value. Values that aren't
+    /// paths (e.g. aliasing one package to another) are skipped.
+    fn replacements(&self, value: &Expr) -> Option<Vec<String>> {
+        let values = match unwrap_expr(value) {
+            Expr::Array(array) => array
+                .elems
+                .iter()
+                .flatten()
+                .filter_map(|e| eval_string(&e.expr, self.dir))
+                .collect(),
+            expr => vec![eval_string(expr, self.dir)?],
+        };
+
+        let paths: Vec<String> = values
+            .into_iter()
+            .filter_map(|value| {
+                if let Some(rest) = value.strip_prefix("<rootDir>") {
+                    Some(format!("{}{}", self.dir.display(), rest))
+                } else if value.starts_with('/') {
+                    Some(value)
+                } else if value.starts_with('.') {
+                    Some(
+                        normalize(&self.dir.join(&value))
+                            .to_string_lossy()
+                            .into_owned(),
+                    )
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        (!paths.is_empty()).then_some(paths)
+    }
+}
+
+impl Visit for AliasVisitor<'_> {
+    fn visit_key_value_prop(&mut self, n: &KeyValueProp) {
+        match prop_name(&n.key).as_deref() {
+            Some("resolve") => {
+                if let Expr::Object(resolve) = unwrap_expr(&n.value) {
+                    for (key, value) in key_values(resolve) {
+                        if key == "alias" {
+                            self.add_resolve_aliases(value);
+                        }
+                    }
+                }
+            }
+            Some("moduleNameMapper") => self.add_module_name_mapper(&n.value),
+            _ => {}
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_array_lit(&mut self, n: &ArrayLit) {
+        let mut elems = n.elems.iter().flatten().map(|e| unwrap_expr(&e.expr));
+        if let (Some(Expr::Lit(Lit::Str(name))), Some(Expr::Object(options))) =
+            (elems.next(), elems.next())
+        {
+            if MODULE_RESOLVER_PLUGINS.contains(&name.value.as_ref()) {
+                self.add_module_resolver(options);
+            }
+        }
+        n.visit_children_with(self);
+    }
+}
+
+fn unwrap_expr(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_expr(&paren.expr),
+        Expr::TsAs(ts_as) => unwrap_expr(&ts_as.expr),
+        Expr::TsSatisfies(satisfies) => unwrap_expr(&satisfies.expr),
+        Expr::TsConstAssertion(assertion) => unwrap_expr(&assertion.expr),
+        _ => expr,
+    }
+}
+
+fn prop_name(name: &PropName) -> Option<String> {
+    match name {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+fn key_values(object: &ObjectLit) -> impl Iterator<Item = (String, &Expr)> {
+    object.props.iter().filter_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(kv) => Some((prop_name(&kv.key)?, &*kv.value)),
+            _ => None,
+        },
+        PropOrSpread::Spread(_) => None,
+    })
+}
+
+/// Statically evaluates the string and path expressions config files use:
+/// literals, `__dirname`, `process.cwd()`, `path.resolve()`/`path.join()` and
+/// `fileURLToPath(new URL('./x', import.meta.url))`
+fn eval_string(expr: &Expr, dir: &Path) -> Option<String> {
+    match unwrap_expr(expr) {
+        Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+            Some(tpl.quasis.first()?.cooked.as_ref()?.to_string())
+        }
+        Expr::Ident(ident) if ident.sym.as_ref() == "__dirname" => {
+            Some(dir.to_string_lossy().into_owned())
+        }
+        Expr::Member(member) => match (&member.prop, unwrap_expr(&member.obj)) {
+            // `new URL(...).pathname`
+            (MemberProp::Ident(prop), Expr::New(_)) if prop.sym.as_ref() == "pathname" => {
+                eval_string(&member.obj, dir)
+            }
+            _ => None,
+        },
+        Expr::New(new) => {
+            let Expr::Ident(callee) = unwrap_expr(&new.callee) else {
+                return None;
+            };
+            let args = new.args.as_ref()?;
+            let is_import_meta_url = args.get(1).is_some_and(|arg| {
+                matches!(unwrap_expr(&arg.expr), Expr::Member(m)
+                    if matches!(unwrap_expr(&m.obj), Expr::MetaProp(meta) if meta.kind == MetaPropKind::ImportMeta))
+            });
+            if callee.sym.as_ref() != "URL" || !is_import_meta_url {
+                return None;
+            }
+            let relative = eval_string(&args.first()?.expr, dir)?;
+            Some(
+                normalize(&dir.join(relative))
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+        Expr::Call(call) => {
+            let Callee::Expr(callee) = &call.callee else {
+                return None;
+            };
+            let name = match unwrap_expr(callee) {
+                Expr::Ident(ident) => ident.sym.to_string(),
+                Expr::Member(member) => member.prop.as_ident()?.sym.to_string(),
+                _ => return None,
+            };
+            match name.as_str() {
+                "cwd" => Some(dir.to_string_lossy().into_owned()),
+                "fileURLToPath" => eval_string(&call.args.first()?.expr, dir),
+                "resolve" | "join" => {
+                    let mut path = PathBuf::new();
+                    for arg in &call.args {
+                        path.push(eval_string(&arg.expr, dir)?);
+                    }
+                    if name == "resolve" && path.is_relative() {
+                        path = dir.join(path);
+                    }
+                    Some(normalize(&path).to_string_lossy().into_owned())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Removes `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// JavaScript replacement strings use `$1`; the regex crate needs `${1}` so
+/// that a following letter isn't read as part of the group name
+fn js_replacement(replacement: String) -> String {
+    static GROUP: std::sync::LazyLock<Regex> =
+        std::sync::LazyLock::new(|| Regex::new(r"\$(\d+)").unwrap());
+    GROUP.replace_all(&replacement, "$${$1}").into_owned()
+}
+
+/// Babel's module-resolver uses `\1` for captures
+fn babel_replacement(replacement: &str) -> String {
+    static GROUP: std::sync::LazyLock<Regex> =
+        std::sync::LazyLock::new(|| Regex::new(r"\\(\d+)").unwrap());
+    GROUP.replace_all(replacement, "$${$1}").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+
+    #[test]
+    fn test_extract_vite_and_jest_aliases() {
+        let mut config = AliasConfig::default();
+        config.extract(
+            Path::new("/app/vite.config.ts"),
+            r#"
+import { defineConfig } from 'vite';
+import path from 'path';
+export default defineConfig({
+  resolve: {
+    alias: {
+      '@': path.resolve(__dirname, './src'),
+      '~lib': fileURLToPath(new URL('./lib', import.meta.url)),
+      'react': 'preact/compat',
+    },
+  },
+});
+"#,
+        );
+        config.extract(
+            Path::new("/app/jest.config.js"),
+            r#"module.exports = { moduleNameMapper: { '^#/(.*)$': '<rootDir>/src/$1' } };"#,
+        );
+
+        assert_eq!(
+            config.resolve_alias("@/utils/format"),
+            Some(vec![PathBuf::from("/app/src/utils/format")])
+        );
+        assert_eq!(
+            config.resolve_alias("~lib"),
+            Some(vec![PathBuf::from("/app/lib")])
+        );
+        assert_eq!(
+            config.resolve_alias("#/store"),
+            Some(vec![PathBuf::from("/app/src/store")])
+        );
+        assert_eq!(config.resolve_alias("react"), None);
+        assert_eq!(config.resolve_alias("@scope/pkg"), None);
+    }
+
+    #[tokio::test]
+    async fn test_package_json_counts_only_with_config_fields() {
+        let fs = MemoryFileSystem::new([
+            (
+                "/repo/vite.config.ts",
+                "export default { resolve: { alias: { '@': '/repo/src' } } };",
+            ),
+            (
+                "/repo/packages/web/package.json",
+                r#"{ "name": "web", "devDependencies": { "jest": "^29.0.0", "babel": "^6.0.0" } }"#,
+            ),
+            (
+                "/repo/packages/api/package.json",
+                r#"{ "name": "api", "jest": { "moduleNameMapper": { "^@/(.*)$": "<rootDir>/lib/$1" } } }"#,
+            ),
+        ]);
+        let resolver = AliasResolver::new();
+        let alias = |from: &'static str| {
+            let resolver = &resolver;
+            let fs = &fs;
+            async move {
+                resolver
+                    .config_for(Path::new(from), fs)
+                    .await
+                    .and_then(|config| config.resolve_alias("@/util"))
+            }
+        };
+
+        assert_eq!(
+            alias("/repo/packages/web/src/main.ts").await,
+            Some(vec![PathBuf::from("/repo/src/util")])
+        );
+        assert_eq!(
+            alias("/repo/packages/api/src/main.ts").await,
+            Some(vec![PathBuf::from("/repo/packages/api/lib/util")])
+        );
+    }
+}
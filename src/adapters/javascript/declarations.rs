@@ -0,0 +1,356 @@
+use super::package_json::{is_declaration, split_package_name, PackageResolver};
+use super::parser::{parse_module, syntax_for_file};
+use super::resolver::NODE_BUILTINS;
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
+use path_absolutize::Absolutize;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use swc_ecma_ast::{
+    ExportAll, ExportSpecifier, ImportDecl, NamedExport, TsImportEqualsDecl, TsImportType,
+    TsModuleRef,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+static REFERENCE_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*///\s*<reference\s+path\s*=\s*["']([^"']+)["']"#).unwrap()
+});
+
+/// Locates the `.d.ts` files describing the external packages a bundle
+/// imports from
+#[derive(Default)]
+pub struct DeclarationCollector {
+    packages: PackageResolver,
+}
+
+/// A reference from one declaration file to another
+struct DeclarationEdge {
+    specifier: String,
+    /// Names re-exported through this edge, or `None` when it must always be
+    /// followed
+    names: Option<Vec<String>>,
+}
+
+impl DeclarationCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declaration files for every bare specifier in `imports`, by package
+    /// name. Each package's entry declaration comes first, followed by the
+    /// files it references that can contribute the imported names.
+    pub async fn collect<'a>(
+        &self,
+        imports: impl IntoIterator<Item = (&'a Path, &'a ImportStatement)>,
+        fs: &dyn FileSystemProvider,
+    ) -> BTreeMap<String, Vec<PathBuf>> {
+        // Imported names per declaration entry; `None` means everything
+        let mut entries: BTreeMap<PathBuf, (String, Option<HashSet<String>>)> = BTreeMap::new();
+
+        for (from_file, import) in imports {
+            let specifier = import.specifier.as_str();
+            if specifier.starts_with(['.', '/', '#']) || specifier.starts_with("node:") {
+                continue;
+            }
+            let Some((name, _)) = split_package_name(specifier) else {
+                continue;
+            };
+            if NODE_BUILTINS.contains(&name) {
+                continue;
+            }
+
+            let Some(entry) = self.entry_declaration(specifier, from_file, fs).await else {
+                log::debug!("No type declarations found for '{}'", specifier);
+                continue;
+            };
+
+            let names = imported_names(import);
+            let (_, wanted) = entries
+                .entry(entry)
+                .or_insert_with(|| (name.to_string(), Some(HashSet::new())));
+            match (wanted.as_mut(), names) {
+                (Some(wanted), Some(names)) => wanted.extend(names),
+                _ => *wanted = None,
+            }
+        }
+
+        let mut packages: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for (entry, (name, wanted)) in entries {
+            let files = packages.entry(name).or_default();
+            for file in self.reachable(entry, wanted.as_ref(), fs).await {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        packages
+    }
+
+    async fn entry_declaration(
+        &self,
+        specifier: &str,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<PathBuf> {
+        for candidate in self
+            .packages
+            .declaration_candidates(specifier, from_file, fs)
+            .await
+        {
+            if let Some(found) = declaration_file(&candidate, fs).await {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Declaration files reachable from `entry`. Re-exports in the entry
+    /// itself are only followed when they can provide one of `wanted`.
+    async fn reachable(
+        &self,
+        entry: PathBuf,
+        wanted: Option<&HashSet<String>>,
+        fs: &dyn FileSystemProvider,
+    ) -> Vec<PathBuf> {
+        let mut seen = HashSet::from([entry.clone()]);
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([entry]);
+
+        while let Some(file) = queue.pop_front() {
+            let is_entry = order.is_empty();
+            order.push(file.clone());
+
+            let Ok(content) = fs.read_file(&file).await else {
+                continue;
+            };
+            let dir = file.parent().unwrap_or_else(|| Path::new("/"));
+
+            for edge in declaration_edges(&file, &content) {
+                if !edge.specifier.starts_with('.') {
+                    continue;
+                }
+                if let (true, Some(wanted), Some(names)) = (is_entry, wanted, &edge.names) {
+                    if !names.iter().any(|n| wanted.contains(n)) {
+                        continue;
+                    }
+                }
+                let Ok(base) = dir
+                    .join(&edge.specifier)
+                    .absolutize()
+                    .map(|p| p.to_path_buf())
+                else {
+                    continue;
+                };
+                if let Some(target) = declaration_file(&base, fs).await {
+                    if seen.insert(target.clone()) {
+                        queue.push_back(target);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+}
+
+/// The declaration file for a module path, which may name the declaration
+/// itself, the JavaScript file it describes, or a directory
+async fn declaration_file(path: &Path, fs: &dyn FileSystemProvider) -> Option<PathBuf> {
+    let path_str = path.to_string_lossy();
+    let mut candidates = Vec::new();
+
+    if is_declaration(&path_str) {
+        candidates.push(path.to_path_buf());
+    } else {
+        let stem = [".js", ".mjs", ".cjs", ".ts"]
+            .iter()
+            .find_map(|ext| path_str.strip_suffix(ext));
+        let declaration_ext = match path.extension().and_then(|e| e.to_str()) {
+            Some("mjs") => ".d.mts",
+            Some("cjs") => ".d.cts",
+            _ => ".d.ts",
+        };
+        if let Some(stem) = stem {
+            candidates.push(PathBuf::from(format!("{}{}", stem, declaration_ext)));
+        }
+        candidates.push(PathBuf::from(format!("{}.d.ts", path_str)));
+        candidates.push(path.join("index.d.ts"));
+    }
+
+    for candidate in candidates {
+        if fs.exists(&candidate).await && !fs.is_directory(&candidate).await {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// Names brought in by an import, or `None` when the whole module is used
+/// (namespace imports, `export *`, plain `require` and dynamic imports)
+fn imported_names(import: &ImportStatement) -> Option<HashSet<String>> {
+    if import.bindings.is_empty() {
+        // Side-effect imports use no names; other kinds may use anything
+        return matches!(import.kind, ImportKind::EsModule | ImportKind::ReExport)
+            .then(HashSet::new);
+    }
+
+    import
+        .bindings
+        .iter()
+        .map(|binding| match binding {
+            ImportBinding::Default { .. } => Some("default".to_string()),
+            ImportBinding::Named { imported, .. } | ImportBinding::ReExport { imported, .. } => {
+                Some(imported.clone())
+            }
+            ImportBinding::Namespace { .. }
+            | ImportBinding::ReExportNamespace { .. }
+            | ImportBinding::ReExportAll => None,
+        })
+        .collect()
+}
+
+/// Modules a declaration file references, with the names it re-exports from
+/// each. Triple-slash `<reference path>` directives are included.
+fn declaration_edges(file: &Path, content: &str) -> Vec<DeclarationEdge> {
+    let mut edges: Vec<DeclarationEdge> = REFERENCE_PATH
+        .captures_iter(content)
+        .map(|c| DeclarationEdge {
+            specifier: relative_reference(&c[1]),
+            names: None,
+        })
+        .collect();
+
+    match parse_module(file, content, syntax_for_file(file)) {
+        Ok((module, _)) => {
+            let mut visitor = DeclarationVisitor { edges: Vec::new() };
+            module.visit_with(&mut visitor);
+            edges.extend(visitor.edges);
+        }
+        Err(e) => log::debug!("{}", e),
+    }
+
+    // The same module may be referenced once for its names and again in full
+    let mut merged: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    let mut order = Vec::new();
+    for edge in edges {
+        match merged.get_mut(&edge.specifier) {
+            Some(Some(names)) => match edge.names {
+                Some(more) => names.extend(more),
+                None => {
+                    merged.insert(edge.specifier, None);
+                }
+            },
+            Some(None) => {}
+            None => {
+                order.push(edge.specifier.clone());
+                merged.insert(edge.specifier, edge.names);
+            }
+        }
+    }
+    order
+        .into_iter()
+        .map(|specifier| DeclarationEdge {
+            names: merged.remove(&specifier).flatten(),
+            specifier,
+        })
+        .collect()
+}
+
+/// `<reference path>` values are relative even without a leading `./`
+fn relative_reference(path: &str) -> String {
+    if path.starts_with('.') {
+        path.to_string()
+    } else {
+        format!("./{}", path)
+    }
+}
+
+struct DeclarationVisitor {
+    edges: Vec<DeclarationEdge>,
+}
+
+impl DeclarationVisitor {
+    fn add(&mut self, specifier: &str, names: Option<Vec<String>>) {
+        self.edges.push(DeclarationEdge {
+            specifier: specifier.to_string(),
+            names,
+        });
+    }
+}
+
+impl Visit for DeclarationVisitor {
+    fn visit_import_decl(&mut self, n: &ImportDecl) {
+        self.add(&n.src.value, None);
+    }
+
+    fn visit_export_all(&mut self, n: &ExportAll) {
+        self.add(&n.src.value, None);
+    }
+
+    fn visit_named_export(&mut self, n: &NamedExport) {
+        let Some(src) = &n.src else {
+            return;
+        };
+        let names = n
+            .specifiers
+            .iter()
+            .map(|specifier| match specifier {
+                ExportSpecifier::Named(named) => named
+                    .exported
+                    .as_ref()
+                    .unwrap_or(&named.orig)
+                    .atom()
+                    .to_string(),
+                ExportSpecifier::Namespace(namespace) => namespace.name.atom().to_string(),
+                ExportSpecifier::Default(default) => default.exported.sym.to_string(),
+            })
+            .collect();
+        self.add(&src.value, Some(names));
+    }
+
+    fn visit_ts_import_equals_decl(&mut self, n: &TsImportEqualsDecl) {
+        if let TsModuleRef::TsExternalModuleRef(module_ref) = &n.module_ref {
+            self.add(&module_ref.expr.value, None);
+        }
+    }
+
+    fn visit_ts_import_type(&mut self, n: &TsImportType) {
+        self.add(&n.arg.value, None);
+        n.visit_children_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declaration_edges() {
+        let content = r#"/// <reference path="common/array.d.ts" />
+export { z, ZodType as Type } from './external';
+export * from './types';
+import type { Options } from './options';
+export { ZodError } from './external';
+"#;
+        let edges = declaration_edges(Path::new("/pkg/index.d.ts"), content);
+        let summary: Vec<(&str, Option<Vec<String>>)> = edges
+            .iter()
+            .map(|e| (e.specifier.as_str(), e.names.clone()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("./common/array.d.ts", None),
+                (
+                    "./external",
+                    Some(vec!["z".into(), "Type".into(), "ZodError".into()])
+                ),
+                ("./types", None),
+                ("./options", None),
+            ]
+        );
+    }
+}
@@ -1,7 +1,20 @@
+mod aliases;
+mod declarations;
+mod package_json;
 mod parser;
 mod resolver;
+mod sfc;
+mod signatures;
+mod slice;
 mod tsconfig_parser;
+mod workspace;
 
+pub use aliases::ALIAS_CONFIG_FILES;
+pub use declarations::DeclarationCollector;
+pub use signatures::signatures;
+pub use slice::{slice_module, SlicedModule, SLICEABLE_EXTENSIONS};
+
+use crate::config::JavaScriptConfig;
 use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -15,6 +28,12 @@ pub struct JsAdapter {
     resolver: JsResolver,
 }
 
+impl Default for JsAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl JsAdapter {
     pub fn new() -> Self {
         Self {
@@ -22,6 +41,11 @@ impl JsAdapter {
             resolver: JsResolver::new(),
         }
     }
+
+    pub fn with_config(mut self, config: &JavaScriptConfig) -> Self {
+        self.resolver = self.resolver.with_config(config);
+        self
+    }
 }
 
 #[async_trait]
@@ -56,7 +80,7 @@ impl LanguageAdapter for JsAdapter {
         context: &AnalysisContext,
     ) -> Result<Option<ResolvedImport>> {
         self.resolver
-            .resolve(&import.specifier, from_file, &*context.fs)
+            .resolve(&import.specifier, &import.kind, from_file, &*context.fs)
             .await
     }
 }
@@ -0,0 +1,503 @@
+use crate::adapters::javascript::workspace::{workspace_patterns, Workspace};
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::ImportKind;
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The parts of a `package.json` that affect resolution
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PackageJson {
+    pub name: Option<String>,
+    pub main: Option<String>,
+    pub module: Option<String>,
+    pub types: Option<String>,
+    pub typings: Option<String>,
+    /// Source entry point used by some bundlers (e.g. microbundle, Parcel)
+    pub source: Option<String>,
+    pub exports: Option<Value>,
+    pub imports: Option<Value>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, Value>,
+    #[serde(default, rename = "devDependencies")]
+    pub dev_dependencies: HashMap<String, Value>,
+    #[serde(default, rename = "peerDependencies")]
+    pub peer_dependencies: HashMap<String, Value>,
+    /// Directory containing the `package.json`
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+impl PackageJson {
+    /// Whether `name` is declared as an installed dependency. Dependencies on
+    /// workspace or linked directories (`workspace:`, `file:`, `link:`) are
+    /// not counted.
+    pub fn depends_on(&self, name: &str) -> bool {
+        [
+            &self.dependencies,
+            &self.dev_dependencies,
+            &self.peer_dependencies,
+        ]
+        .iter()
+        .filter_map(|deps| deps.get(name))
+        .any(|version| {
+            !version.as_str().is_some_and(|v| {
+                ["workspace:", "file:", "link:", "portal:"]
+                    .iter()
+                    .any(|protocol| v.starts_with(protocol))
+            })
+        })
+    }
+}
+
+/// Finds `package.json` files and turns bare and `#` specifiers into candidate
+/// paths through their `exports`, `imports` and entry fields
+#[derive(Default)]
+pub struct PackageResolver {
+    cache: DashMap<PathBuf, Option<Arc<PackageJson>>>,
+    /// Workspace containing each directory looked up so far
+    workspaces: DashMap<PathBuf, Option<Arc<Workspace>>>,
+}
+
+impl PackageResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Candidate files for a bare or `#` specifier, most preferred first.
+    ///
+    /// Returns nothing when no `package.json` matches the specifier. The
+    /// candidates may live in `node_modules`; callers decide whether the
+    /// package is local.
+    pub async fn candidates(
+        &self,
+        specifier: &str,
+        kind: &ImportKind,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Vec<PathBuf> {
+        let conditions = conditions_for(kind);
+
+        if specifier.starts_with('#') {
+            let Some(package) = self.nearest(from_file, fs).await else {
+                return Vec::new();
+            };
+            let Some(Value::Object(imports)) = &package.imports else {
+                return Vec::new();
+            };
+            return match match_subpath(imports, specifier) {
+                Some((target, star)) => resolve_targets(&package.dir, target, star, conditions),
+                None => Vec::new(),
+            };
+        }
+
+        let Some((name, subpath)) = split_package_name(specifier) else {
+            return Vec::new();
+        };
+
+        // A package may import itself by name, and workspace packages are
+        // used from source whether or not they are linked into node_modules
+        let (package, in_workspace) = match self.nearest(from_file, fs).await {
+            Some(package) if package.name.as_deref() == Some(name) => (Some(package), true),
+            _ => match self.workspace_package(name, from_file, fs).await {
+                Some(package) => (Some(package), true),
+                None => (self.installed(name, from_file, fs).await, false),
+            },
+        };
+        let Some(package) = package else {
+            return Vec::new();
+        };
+
+        let candidates = package_candidates(&package, &subpath, kind, conditions);
+        if !in_workspace {
+            return candidates;
+        }
+
+        // Prefer the package's sources over its build output
+        let mut preferred = Vec::new();
+        if subpath == "." {
+            let types = package.types.as_ref().filter(|t| !is_declaration(t));
+            preferred.extend(
+                package
+                    .source
+                    .iter()
+                    .chain(types)
+                    .map(|f| package.dir.join(f)),
+            );
+        }
+        for candidate in candidates {
+            preferred.extend(source_for_build_output(&package.dir, &candidate));
+            preferred.push(candidate);
+        }
+        preferred
+    }
+
+    /// Candidate declaration entry points for an installed package, followed
+    /// by those of its `@types` package. Candidates may be JavaScript files or
+    /// directories that the declarations sit next to.
+    pub async fn declaration_candidates(
+        &self,
+        specifier: &str,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Vec<PathBuf> {
+        let Some((name, subpath)) = split_package_name(specifier) else {
+            return Vec::new();
+        };
+        // `@scope/pkg` is typed by `@types/scope__pkg`
+        let types_package = match name.strip_prefix('@') {
+            Some(scoped) => format!("@types/{}", scoped.replace('/', "__")),
+            None => format!("@types/{}", name),
+        };
+
+        let kind = ImportKind::TypeOnly;
+        let mut candidates = Vec::new();
+        for name in [name, types_package.as_str()] {
+            if let Some(package) = self.installed(name, from_file, fs).await {
+                candidates.extend(package_candidates(
+                    &package,
+                    &subpath,
+                    &kind,
+                    conditions_for(&kind),
+                ));
+            }
+        }
+        candidates
+    }
+
+    /// Package `name` from the workspace containing `from_file`
+    pub async fn workspace_package(
+        &self,
+        name: &str,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<Arc<PackageJson>> {
+        let workspace = self.workspace(from_file.parent()?, fs).await?;
+        let dir = workspace.packages.get(name)?;
+        self.read(&dir.join("package.json"), fs).await
+    }
+
+    async fn workspace(&self, dir: &Path, fs: &dyn FileSystemProvider) -> Option<Arc<Workspace>> {
+        let mut visited = Vec::new();
+        let mut current = Some(dir);
+        let mut found = None;
+
+        while let Some(dir) = current {
+            if let Some(cached) = self.workspaces.get(dir) {
+                found = cached.clone();
+                break;
+            }
+            visited.push(dir.to_path_buf());
+            if dir.file_name().is_some_and(|n| n == "node_modules") {
+                break;
+            }
+            if let Some(patterns) = workspace_patterns(dir, fs).await {
+                found = Some(Arc::new(Workspace::load(dir, &patterns, fs).await));
+                break;
+            }
+            current = dir.parent();
+        }
+
+        for dir in visited {
+            self.workspaces.insert(dir, found.clone());
+        }
+        found
+    }
+
+    /// Nearest `package.json` above `from_file`
+    pub async fn nearest(
+        &self,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<Arc<PackageJson>> {
+        let mut current = from_file.parent();
+        while let Some(dir) = current {
+            if dir.file_name().is_some_and(|n| n == "node_modules") {
+                return None;
+            }
+            if let Some(package) = self.read(&dir.join("package.json"), fs).await {
+                return Some(package);
+            }
+            current = dir.parent();
+        }
+        None
+    }
+
+    /// Package `name` as Node would find it in a `node_modules` directory
+    /// above `from_file`
+    async fn installed(
+        &self,
+        name: &str,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<Arc<PackageJson>> {
+        let mut current = from_file.parent();
+        while let Some(dir) = current {
+            if dir.file_name().is_some_and(|n| n != "node_modules") {
+                let manifest = dir.join("node_modules").join(name).join("package.json");
+                if let Some(package) = self.read(&manifest, fs).await {
+                    return Some(package);
+                }
+            }
+            current = dir.parent();
+        }
+        None
+    }
+
+    async fn read(&self, path: &Path, fs: &dyn FileSystemProvider) -> Option<Arc<PackageJson>> {
+        if let Some(cached) = self.cache.get(path) {
+            return cached.clone();
+        }
+
+        let package = if fs.exists(path).await {
+            match fs.read_file(path).await.map(|c| serde_json::from_str(&c)) {
+                Ok(Ok(package)) => Some(Arc::new(PackageJson {
+                    dir: path
+                        .parent()
+                        .unwrap_or_else(|| Path::new("/"))
+                        .to_path_buf(),
+                    ..package
+                })),
+                Ok(Err(e)) => {
+                    log::warn!("Failed to parse {}: {}", path.display(), e);
+                    None
+                }
+                Err(e) => {
+                    log::warn!("Failed to read {}: {}", path.display(), e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        self.cache.insert(path.to_path_buf(), package.clone());
+        package
+    }
+}
+
+/// Files `subpath` of `package` may resolve to, from its `exports` field or
+/// else its entry fields
+fn package_candidates(
+    package: &PackageJson,
+    subpath: &str,
+    kind: &ImportKind,
+    conditions: &[&str],
+) -> Vec<PathBuf> {
+    if let Some(exports) = &package.exports {
+        return match exports_target(exports, subpath) {
+            Some((target, star)) => resolve_targets(&package.dir, target, star, conditions),
+            None => {
+                log::debug!("'{}' is not exported by {}", subpath, package.dir.display());
+                Vec::new()
+            }
+        };
+    }
+
+    if subpath != "." {
+        return vec![package.dir.join(&subpath[2..])];
+    }
+
+    let mut fields = Vec::new();
+    if matches!(kind, ImportKind::TypeOnly) {
+        fields.extend([&package.types, &package.typings]);
+    }
+    if !matches!(kind, ImportKind::CommonJs) {
+        fields.push(&package.module);
+    }
+    fields.push(&package.main);
+
+    let mut candidates: Vec<PathBuf> = fields
+        .into_iter()
+        .flatten()
+        .map(|field| package.dir.join(field))
+        .collect();
+    candidates.push(package.dir.clone());
+    candidates
+}
+
+/// Guesses the source file a build output was compiled from, e.g.
+/// `dist/button.d.ts` -> `src/button` (extension left for resolution)
+fn source_for_build_output(package_dir: &Path, path: &Path) -> Option<PathBuf> {
+    const BUILD_DIRS: &[&str] = &["dist", "build", "lib", "out"];
+    const BUILD_EXTENSIONS: &[&str] = &[".d.ts", ".d.mts", ".d.cts", ".js", ".mjs", ".cjs"];
+
+    let relative = path.strip_prefix(package_dir).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    if !BUILD_DIRS.contains(&first) {
+        return None;
+    }
+
+    let rest = components.as_path().to_str()?;
+    let stem = BUILD_EXTENSIONS
+        .iter()
+        .find_map(|ext| rest.strip_suffix(ext))
+        .unwrap_or(rest);
+    Some(package_dir.join("src").join(stem))
+}
+
+pub(super) fn is_declaration(path: &str) -> bool {
+    path.ends_with(".d.ts") || path.ends_with(".d.mts") || path.ends_with(".d.cts")
+}
+
+/// Conditions tried for an import, in order. `serde_json` doesn't keep object
+/// key order, so these take precedence over the order in `package.json`.
+fn conditions_for(kind: &ImportKind) -> &'static [&'static str] {
+    match kind {
+        ImportKind::TypeOnly => &["types", "import", "module", "node", "default"],
+        ImportKind::CommonJs => &["require", "node", "default"],
+        _ => &["import", "module", "node", "default"],
+    }
+}
+
+/// Splits `@scope/name/sub/path` into `@scope/name` and `./sub/path`
+pub(super) fn split_package_name(specifier: &str) -> Option<(&str, String)> {
+    let mut parts = specifier.splitn(if specifier.starts_with('@') { 3 } else { 2 }, '/');
+    let first = parts.next()?;
+    let name_len = if specifier.starts_with('@') {
+        first.len() + 1 + parts.next()?.len()
+    } else {
+        first.len()
+    };
+    if name_len == 0 || specifier.starts_with('.') {
+        return None;
+    }
+
+    let name = &specifier[..name_len];
+    let subpath = match parts.next() {
+        Some(rest) if !rest.is_empty() => format!("./{}", rest),
+        _ => ".".to_string(),
+    };
+    Some((name, subpath))
+}
+
+/// Target of `subpath` in an `exports` field, which may be a single target,
+/// a condition map for `.` or a map of subpaths
+fn exports_target<'a>(exports: &'a Value, subpath: &str) -> Option<(&'a Value, Option<String>)> {
+    match exports {
+        Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => match_subpath(map, subpath),
+        _ if subpath == "." => Some((exports, None)),
+        _ => None,
+    }
+}
+
+/// Finds the entry for `key` in an `exports`/`imports` map: an exact match, or
+/// the `*` pattern with the longest prefix, returning what `*` matched
+fn match_subpath<'a>(
+    map: &'a Map<String, Value>,
+    key: &str,
+) -> Option<(&'a Value, Option<String>)> {
+    if let Some(target) = map.get(key) {
+        return Some((target, None));
+    }
+
+    map.iter()
+        .filter_map(|(pattern, target)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let matched = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), target, matched.to_string()))
+        })
+        .max_by_key(|(prefix_len, _, _)| *prefix_len)
+        .map(|(_, target, matched)| (target, Some(matched)))
+}
+
+/// Expands a target (string, condition map or fallback array) into paths
+fn resolve_targets(
+    package_dir: &Path,
+    target: &Value,
+    star: Option<String>,
+    conditions: &[&str],
+) -> Vec<PathBuf> {
+    let mut targets = Vec::new();
+    collect_targets(target, conditions, &mut targets);
+
+    targets
+        .into_iter()
+        .filter_map(|target| {
+            let target = match &star {
+                Some(matched) => target.replace('*', matched),
+                None => target.to_string(),
+            };
+            // Targets must stay inside the package
+            target.strip_prefix("./").map(|t| package_dir.join(t))
+        })
+        .collect()
+}
+
+fn collect_targets<'a>(target: &'a Value, conditions: &[&str], out: &mut Vec<&'a str>) {
+    match target {
+        Value::String(path) => out.push(path),
+        Value::Array(fallbacks) => {
+            for fallback in fallbacks {
+                collect_targets(fallback, conditions, out);
+            }
+        }
+        Value::Object(map) => {
+            for condition in conditions {
+                if let Some(nested) = map.get(*condition) {
+                    collect_targets(nested, conditions, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_split_package_name() {
+        assert_eq!(
+            split_package_name("@acme/ui/button"),
+            Some(("@acme/ui", "./button".to_string()))
+        );
+        assert_eq!(
+            split_package_name("lodash"),
+            Some(("lodash", ".".to_string()))
+        );
+        assert_eq!(split_package_name("@acme"), None);
+    }
+
+    #[test]
+    fn test_exports_conditions_and_patterns() {
+        let exports = json!({
+            ".": { "require": "./dist/index.cjs", "import": "./src/index.ts" },
+            "./components/*": { "types": "./src/components/*.d.ts", "default": "./src/components/*.tsx" },
+        });
+        let dir = Path::new("/repo/packages/ui");
+
+        let (target, star) = exports_target(&exports, ".").unwrap();
+        assert_eq!(
+            resolve_targets(dir, target, star, conditions_for(&ImportKind::EsModule)),
+            vec![dir.join("src/index.ts")]
+        );
+
+        let (target, star) = exports_target(&exports, "./components/Button").unwrap();
+        assert_eq!(
+            resolve_targets(dir, target, star, conditions_for(&ImportKind::EsModule)),
+            vec![dir.join("src/components/Button.tsx")]
+        );
+        assert!(exports_target(&exports, "./internal").is_none());
+    }
+
+    #[test]
+    fn test_depends_on_skips_linked_packages() {
+        let package: PackageJson = serde_json::from_value(json!({
+            "dependencies": { "vue": "^3.4.0", "@acme/ui": "workspace:*" },
+            "devDependencies": { "vitest": "^1.0.0" },
+        }))
+        .unwrap();
+
+        assert!(package.depends_on("vue"));
+        assert!(package.depends_on("vitest"));
+        assert!(!package.depends_on("@acme/ui"));
+        assert!(!package.depends_on("vue-helpers"));
+    }
+}
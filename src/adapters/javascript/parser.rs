@@ -1,9 +1,14 @@
-use crate::core::language::{ImportKind, ImportStatement};
+use super::sfc;
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Arc;
 use swc_common::{SourceMap, SourceMapper, Span};
-use swc_ecma_ast::{ArrowExpr, BlockStmtOrExpr, CallExpr, ExportAll, ExportDecl, ImportDecl, Lit};
+use swc_ecma_ast::{
+    ArrowExpr, BlockStmtOrExpr, CallExpr, ExportAll, ExportDecl, ExportSpecifier, ImportDecl,
+    ImportSpecifier, Lit, Module, NamedExport, ObjectPatProp, Pat, PropName, TsImportEqualsDecl,
+    TsImportType, TsModuleRef, VarDeclarator,
+};
 use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
 use swc_ecma_visit::{VisitMut, VisitMutWith};
 
@@ -16,25 +21,26 @@ impl JsParser {
     }
 
     pub fn parse(&self, file_path: &Path, content: &str) -> Result<Vec<ImportStatement>> {
-        let cm = Arc::<SourceMap>::default();
-        let fm = cm.new_source_file(
-            swc_common::FileName::Real(file_path.to_path_buf()).into(),
-            content.to_string(),
-        );
-
-        let syntax = syntax_for_file(file_path);
+        let extension = file_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .unwrap_or_default();
 
-        let lexer = Lexer::new(syntax, Default::default(), StringInput::from(&*fm), None);
+        if sfc::is_sfc_extension(&extension) {
+            return self.parse_sfc(file_path, content);
+        }
 
-        let mut parser = Parser::new_from(lexer);
-        let module = parser
-            .parse_module()
-            .map_err(|e| anyhow::anyhow!("SWC parse error in {}: {:?}", file_path.display(), e));
+        self.parse_script(file_path, content, syntax_for_file(file_path))
+    }
 
-        let mut module = match module {
-            Ok(m) => m,
-            Err(e) => return Err(e),
-        };
+    fn parse_script(
+        &self,
+        file_path: &Path,
+        content: &str,
+        syntax: Syntax,
+    ) -> Result<Vec<ImportStatement>> {
+        let (mut module, cm) = parse_module(file_path, content, syntax)?;
 
         let mut visitor = ImportVisitor {
             imports: Vec::new(),
@@ -45,34 +51,94 @@ impl JsParser {
 
         Ok(visitor.imports)
     }
+
+    /// Parses the `<script>` blocks of a Vue/Svelte component with the syntax
+    /// given by their `lang` attribute, and collects stylesheet references
+    fn parse_sfc(&self, file_path: &Path, content: &str) -> Result<Vec<ImportStatement>> {
+        let mut imports = Vec::new();
+
+        for block in sfc::extract_script_blocks(content) {
+            if let Some(src) = block.src {
+                imports.push(ImportStatement {
+                    specifier: src,
+                    kind: ImportKind::EsModule,
+                    line: block.line,
+                    column: block.column,
+                    raw: block.raw_tag,
+                    bindings: Vec::new(),
+                });
+                continue;
+            }
+
+            let syntax = syntax_for_lang(block.lang.as_deref().unwrap_or("js"), false);
+            for mut import in self.parse_script(file_path, &block.content, syntax)? {
+                (import.line, import.column) = block.map_position(import.line, import.column);
+                imports.push(import);
+            }
+        }
+
+        imports.extend(sfc::extract_style_imports(content));
+
+        Ok(imports)
+    }
+}
+
+/// Parses `content` into a module AST along with its source map
+pub(super) fn parse_module(
+    file_path: &Path,
+    content: &str,
+    syntax: Syntax,
+) -> Result<(Module, Arc<SourceMap>)> {
+    let cm = Arc::<SourceMap>::default();
+    let fm = cm.new_source_file(
+        swc_common::FileName::Real(file_path.to_path_buf()).into(),
+        content.to_string(),
+    );
+
+    let lexer = Lexer::new(syntax, Default::default(), StringInput::from(&*fm), None);
+
+    let mut parser = Parser::new_from(lexer);
+    let module = parser
+        .parse_module()
+        .map_err(|e| anyhow::anyhow!("SWC parse error in {}: {:?}", file_path.display(), e))?;
+
+    Ok((module, cm))
 }
 
-fn syntax_for_file(file_path: &Path) -> Syntax {
+pub(super) fn syntax_for_file(file_path: &Path) -> Syntax {
     let is_d_ts = file_path
         .file_name()
         .and_then(|name| name.to_str())
         .map(|name| name.ends_with(".d.ts"))
         .unwrap_or(false);
 
-    match file_path
+    let extension = file_path
         .extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_ascii_lowercase())
-        .as_deref()
-    {
-        Some("tsx") => Syntax::Typescript(TsSyntax {
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some(ext @ ("tsx" | "jsx" | "ts")) => syntax_for_lang(ext, is_d_ts),
+        _ => Syntax::Typescript(TsSyntax::default()),
+    }
+}
+
+/// Syntax for a language name as used in extensions and `lang` attributes
+fn syntax_for_lang(lang: &str, is_d_ts: bool) -> Syntax {
+    match lang {
+        "tsx" => Syntax::Typescript(TsSyntax {
             tsx: true,
             ..Default::default()
         }),
-        Some("jsx") => Syntax::Es(EsSyntax {
+        "jsx" => Syntax::Es(EsSyntax {
             jsx: true,
             ..Default::default()
         }),
-        Some("ts") => Syntax::Typescript(TsSyntax {
+        "ts" | "typescript" => Syntax::Typescript(TsSyntax {
             dts: is_d_ts,
             ..Default::default()
         }),
-        _ => Syntax::Typescript(TsSyntax::default()),
+        _ => Syntax::Es(EsSyntax::default()),
     }
 }
 
@@ -83,6 +149,16 @@ struct ImportVisitor {
 
 impl ImportVisitor {
     fn add_import(&mut self, specifier: String, kind: ImportKind, span: Span) {
+        self.add_import_with_bindings(specifier, kind, span, Vec::new());
+    }
+
+    fn add_import_with_bindings(
+        &mut self,
+        specifier: String,
+        kind: ImportKind,
+        span: Span,
+        bindings: Vec<ImportBinding>,
+    ) {
         let loc = self.source_map.lookup_char_pos(span.lo);
         self.imports.push(ImportStatement {
             specifier,
@@ -90,6 +166,7 @@ impl ImportVisitor {
             line: loc.line,
             column: loc.col_display,
             raw: self.source_map.span_to_snippet(span).unwrap_or_default(),
+            bindings,
         });
     }
 
@@ -100,10 +177,8 @@ impl ImportVisitor {
             BlockStmtOrExpr::Expr(expr) => {
                 if let Some(call_expr) = expr.as_call() {
                     if call_expr.callee.is_import() && call_expr.args.len() == 1 {
-                        if let Some(lit) = call_expr.args[0].expr.as_lit() {
-                            if let Lit::Str(s) = lit {
-                                return Some(s.value.to_string());
-                            }
+                        if let Some(Lit::Str(s)) = call_expr.args[0].expr.as_lit() {
+                            return Some(s.value.to_string());
                         }
                     }
                 }
@@ -115,10 +190,8 @@ impl ImportVisitor {
                         if let Some(arg) = &return_stmt.arg {
                             if let Some(call_expr) = arg.as_call() {
                                 if call_expr.callee.is_import() && call_expr.args.len() == 1 {
-                                    if let Some(lit) = call_expr.args[0].expr.as_lit() {
-                                        if let Lit::Str(s) = lit {
-                                            return Some(s.value.to_string());
-                                        }
+                                    if let Some(Lit::Str(s)) = call_expr.args[0].expr.as_lit() {
+                                        return Some(s.value.to_string());
                                     }
                                 }
                             }
@@ -134,7 +207,49 @@ impl ImportVisitor {
 impl VisitMut for ImportVisitor {
     fn visit_mut_import_decl(&mut self, n: &mut ImportDecl) {
         let specifier = n.src.value.to_string();
-        self.add_import(specifier, ImportKind::EsModule, n.span);
+        let bindings: Vec<ImportBinding> = n
+            .specifiers
+            .iter()
+            .map(|specifier| match specifier {
+                ImportSpecifier::Default(default) => ImportBinding::Default {
+                    local: default.local.sym.to_string(),
+                    type_only: n.type_only,
+                },
+                ImportSpecifier::Named(named) => ImportBinding::Named {
+                    imported: named
+                        .imported
+                        .as_ref()
+                        .map_or_else(|| named.local.sym.to_string(), |i| i.atom().to_string()),
+                    local: named.local.sym.to_string(),
+                    type_only: n.type_only || named.is_type_only,
+                },
+                ImportSpecifier::Namespace(namespace) => ImportBinding::Namespace {
+                    local: namespace.local.sym.to_string(),
+                    type_only: n.type_only,
+                },
+            })
+            .collect();
+        let kind = if n.type_only || all_type_only(&bindings) {
+            ImportKind::TypeOnly
+        } else {
+            ImportKind::EsModule
+        };
+        self.add_import_with_bindings(specifier, kind, n.span, bindings);
+        n.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_var_declarator(&mut self, n: &mut VarDeclarator) {
+        // `const x = require('x')` and `const { a, b: c } = require('x')`
+        if let Some(specifier) = n
+            .init
+            .as_ref()
+            .and_then(|init| init.as_call())
+            .and_then(require_specifier)
+        {
+            let bindings = require_bindings(&n.name);
+            self.add_import_with_bindings(specifier, ImportKind::CommonJs, n.span, bindings);
+            return;
+        }
         n.visit_mut_children_with(self);
     }
 
@@ -166,21 +281,84 @@ impl VisitMut for ImportVisitor {
 
     fn visit_mut_export_all(&mut self, n: &mut ExportAll) {
         let specifier = n.src.value.to_string();
-        self.add_import(specifier, ImportKind::EsModule, n.span);
+        let kind = if n.type_only {
+            ImportKind::TypeOnly
+        } else {
+            ImportKind::ReExport
+        };
+        self.add_import_with_bindings(specifier, kind, n.span, vec![ImportBinding::ReExportAll]);
+        n.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_named_export(&mut self, n: &mut NamedExport) {
+        // `export { x }` without a source only re-exports local names
+        let Some(src) = &n.src else {
+            return;
+        };
+        let bindings: Vec<ImportBinding> = n
+            .specifiers
+            .iter()
+            .map(|specifier| match specifier {
+                ExportSpecifier::Named(named) => ImportBinding::ReExport {
+                    imported: named.orig.atom().to_string(),
+                    exported: named
+                        .exported
+                        .as_ref()
+                        .unwrap_or(&named.orig)
+                        .atom()
+                        .to_string(),
+                    type_only: n.type_only || named.is_type_only,
+                },
+                ExportSpecifier::Namespace(namespace) => ImportBinding::ReExportNamespace {
+                    exported: namespace.name.atom().to_string(),
+                },
+                // `export x from './x'` (proposal syntax) re-exports the default
+                ExportSpecifier::Default(default) => ImportBinding::ReExport {
+                    imported: "default".to_string(),
+                    exported: default.exported.sym.to_string(),
+                    type_only: n.type_only,
+                },
+            })
+            .collect();
+        let kind = if n.type_only || all_type_only(&bindings) {
+            ImportKind::TypeOnly
+        } else {
+            ImportKind::ReExport
+        };
+        self.add_import_with_bindings(src.value.to_string(), kind, n.span, bindings);
+    }
+
+    /// `import x = require('./x')`
+    fn visit_mut_ts_import_equals_decl(&mut self, n: &mut TsImportEqualsDecl) {
+        if let TsModuleRef::TsExternalModuleRef(module_ref) = &n.module_ref {
+            let kind = if n.is_type_only {
+                ImportKind::TypeOnly
+            } else {
+                ImportKind::CommonJs
+            };
+            let bindings = vec![ImportBinding::Namespace {
+                local: n.id.sym.to_string(),
+                type_only: n.is_type_only,
+            }];
+            self.add_import_with_bindings(
+                module_ref.expr.value.to_string(),
+                kind,
+                n.span,
+                bindings,
+            );
+        }
+    }
+
+    /// `import("./x").Foo` in a type position
+    fn visit_mut_ts_import_type(&mut self, n: &mut TsImportType) {
+        self.add_import(n.arg.value.to_string(), ImportKind::TypeOnly, n.span);
         n.visit_mut_children_with(self);
     }
 
     fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
         // Handle require() calls
-        if let Some(ident) = n.callee.as_expr().and_then(|e| e.as_ident()) {
-            if ident.sym.as_ref() == "require" && n.args.len() == 1 {
-                if let Some(lit) = n.args[0].expr.as_lit() {
-                    if let Lit::Str(s) = lit {
-                        let specifier = s.value.to_string();
-                        self.add_import(specifier, ImportKind::CommonJs, n.span);
-                    }
-                }
-            }
+        if let Some(specifier) = require_specifier(n) {
+            self.add_import(specifier, ImportKind::CommonJs, n.span);
         }
 
         // Handle React.lazy(() => import(...)) patterns
@@ -193,7 +371,7 @@ impl VisitMut for ImportVisitor {
                 false
             };
 
-            if is_react_lazy && n.args.len() >= 1 {
+            if is_react_lazy && !n.args.is_empty() {
                 if let Some(arrow) = n.args[0].expr.as_arrow() {
                     if let Some(specifier) = self.extract_import_from_arrow(arrow) {
                         self.add_import(specifier, ImportKind::Dynamic, n.span);
@@ -203,7 +381,7 @@ impl VisitMut for ImportVisitor {
         }
 
         if let Some(ident) = n.callee.as_expr().and_then(|e| e.as_ident()) {
-            if ident.sym.as_ref() == "lazy" && n.args.len() >= 1 {
+            if ident.sym.as_ref() == "lazy" && !n.args.is_empty() {
                 if let Some(arrow) = n.args[0].expr.as_arrow() {
                     if let Some(specifier) = self.extract_import_from_arrow(arrow) {
                         self.add_import(specifier, ImportKind::Dynamic, n.span);
@@ -213,14 +391,192 @@ impl VisitMut for ImportVisitor {
         }
 
         if n.callee.is_import() && n.args.len() == 1 {
-            if let Some(lit) = n.args[0].expr.as_lit() {
-                if let Lit::Str(s) = lit {
-                    let specifier = s.value.to_string();
-                    self.add_import(specifier, ImportKind::Dynamic, n.span);
-                }
+            if let Some(Lit::Str(s)) = n.args[0].expr.as_lit() {
+                let specifier = s.value.to_string();
+                self.add_import(specifier, ImportKind::Dynamic, n.span);
             }
         }
 
         n.visit_mut_children_with(self);
     }
 }
+
+/// Whether every binding is type-only, so the import is erased at runtime.
+/// Side-effect imports have no bindings and always run.
+fn all_type_only(bindings: &[ImportBinding]) -> bool {
+    !bindings.is_empty()
+        && bindings.iter().all(|binding| match binding {
+            ImportBinding::Default { type_only, .. }
+            | ImportBinding::Named { type_only, .. }
+            | ImportBinding::Namespace { type_only, .. }
+            | ImportBinding::ReExport { type_only, .. } => *type_only,
+            ImportBinding::ReExportNamespace { .. } | ImportBinding::ReExportAll => false,
+        })
+}
+
+/// Specifier of a `require('x')` call
+fn require_specifier(call: &CallExpr) -> Option<String> {
+    let ident = call.callee.as_expr()?.as_ident()?;
+    if ident.sym.as_ref() != "require" || call.args.len() != 1 {
+        return None;
+    }
+    match call.args[0].expr.as_lit()? {
+        Lit::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Bindings of the pattern a `require` call is assigned to
+fn require_bindings(pat: &Pat) -> Vec<ImportBinding> {
+    match pat {
+        Pat::Ident(ident) => vec![ImportBinding::Namespace {
+            local: ident.id.sym.to_string(),
+            type_only: false,
+        }],
+        Pat::Object(object) => object
+            .props
+            .iter()
+            .filter_map(|prop| match prop {
+                ObjectPatProp::KeyValue(kv) => {
+                    let imported = match &kv.key {
+                        PropName::Ident(ident) => ident.sym.to_string(),
+                        PropName::Str(s) => s.value.to_string(),
+                        _ => return None,
+                    };
+                    let local = kv.value.as_ident()?.id.sym.to_string();
+                    Some(ImportBinding::Named {
+                        imported,
+                        local,
+                        type_only: false,
+                    })
+                }
+                ObjectPatProp::Assign(assign) => Some(ImportBinding::Named {
+                    imported: assign.key.sym.to_string(),
+                    local: assign.key.sym.to_string(),
+                    type_only: false,
+                }),
+                ObjectPatProp::Rest(_) => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_bindings() {
+        let content = r#"
+import def, { a, b as bee, type T } from './a';
+import * as ns from './b';
+const { c, d: dee } = require('./c');
+"#;
+        let imports = JsParser::new()
+            .parse(Path::new("/src/index.ts"), content)
+            .unwrap();
+
+        assert_eq!(
+            imports[0].bindings,
+            vec![
+                ImportBinding::Default {
+                    local: "def".into(),
+                    type_only: false
+                },
+                ImportBinding::Named {
+                    imported: "a".into(),
+                    local: "a".into(),
+                    type_only: false
+                },
+                ImportBinding::Named {
+                    imported: "b".into(),
+                    local: "bee".into(),
+                    type_only: false
+                },
+                ImportBinding::Named {
+                    imported: "T".into(),
+                    local: "T".into(),
+                    type_only: true
+                },
+            ]
+        );
+        assert_eq!(
+            imports[1].bindings,
+            vec![ImportBinding::Namespace {
+                local: "ns".into(),
+                type_only: false
+            }]
+        );
+        assert_eq!(imports.len(), 3);
+        assert!(matches!(imports[2].kind, ImportKind::CommonJs));
+        assert_eq!(
+            imports[2].bindings[1],
+            ImportBinding::Named {
+                imported: "d".into(),
+                local: "dee".into(),
+                type_only: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_named_reexports_are_recorded() {
+        let content = r#"
+export { Button, default as Card } from './Button';
+export * as Icons from './icons';
+export { local };
+"#;
+        let imports = JsParser::new()
+            .parse(Path::new("/src/components/index.ts"), content)
+            .unwrap();
+
+        assert_eq!(imports.len(), 2);
+        assert!(imports
+            .iter()
+            .all(|i| matches!(i.kind, ImportKind::ReExport)));
+        assert_eq!(
+            imports[0].bindings[1],
+            ImportBinding::ReExport {
+                imported: "default".into(),
+                exported: "Card".into(),
+                type_only: false
+            }
+        );
+        assert_eq!(
+            imports[1].bindings,
+            vec![ImportBinding::ReExportNamespace {
+                exported: "Icons".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_type_only_imports() {
+        let content = r#"
+import type { A } from './a';
+import { type B } from './b';
+import { type C, c } from './c';
+export type { D } from './d';
+type E = import('./e').E;
+"#;
+        let imports = JsParser::new()
+            .parse(Path::new("/src/index.ts"), content)
+            .unwrap();
+        let kinds: Vec<(&str, bool)> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), matches!(i.kind, ImportKind::TypeOnly)))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("./a", true),
+                ("./b", true),
+                ("./c", false),
+                ("./d", true),
+                ("./e", true)
+            ]
+        );
+    }
+}
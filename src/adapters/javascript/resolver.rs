@@ -1,6 +1,9 @@
-use crate::adapters::javascript::tsconfig_parser::TsConfigParser;
+use crate::adapters::javascript::aliases::AliasResolver;
+use crate::adapters::javascript::package_json::{split_package_name, PackageResolver};
+use crate::adapters::javascript::tsconfig_parser::{TsConfig, TsConfigParser};
+use crate::config::{JavaScriptConfig, Resolution};
 use crate::core::fs::FileSystemProvider;
-use crate::core::language::ResolvedImport;
+use crate::core::language::{ImportKind, ResolvedImport};
 use anyhow::Result;
 use path_absolutize::Absolutize;
 use std::path::{Path, PathBuf};
@@ -9,26 +12,74 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct JsResolver {
     tsconfig_parser: Arc<TsConfigParser>,
+    packages: Arc<PackageResolver>,
+    aliases: Arc<AliasResolver>,
+    resolution: Resolution,
+    tsconfig_path: Option<PathBuf>,
+    externals: Vec<String>,
+    internals: Vec<String>,
 }
 
 impl JsResolver {
     pub fn new() -> Self {
         Self {
             tsconfig_parser: Arc::new(TsConfigParser::new()),
+            packages: Arc::new(PackageResolver::new()),
+            aliases: Arc::new(AliasResolver::new()),
+            resolution: Resolution::default(),
+            tsconfig_path: None,
+            externals: Vec::new(),
+            internals: Vec::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: &JavaScriptConfig) -> Self {
+        self.resolution = config.resolution.unwrap_or_default();
+        self.tsconfig_path = config.tsconfig_path.clone();
+        self.externals = config.externals.clone().unwrap_or_default();
+        self.internals = config.internals.clone().unwrap_or_default();
+        self
+    }
+
+    /// Returns the tsconfig/jsconfig that applies to `from_file`, honouring the
+    /// configured resolution mode and explicit `tsconfig_path`
+    async fn tsconfig_for(
+        &self,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<Arc<TsConfig>>> {
+        match (self.resolution, &self.tsconfig_path) {
+            (Resolution::Node, _) => Ok(None),
+            (Resolution::Typescript, Some(path)) => {
+                self.tsconfig_parser.parse_config(path, fs).await.map(Some)
+            }
+            (Resolution::Typescript, None) => {
+                self.tsconfig_parser
+                    .find_and_parse_config(from_file, fs)
+                    .await
+            }
         }
     }
 
     pub async fn resolve(
         &self,
         specifier: &str,
+        kind: &ImportKind,
         from_file: &Path,
         fs: &dyn FileSystemProvider,
     ) -> Result<Option<ResolvedImport>> {
-        if self.is_external_package(specifier, from_file, fs).await? {
+        // Subpath imports fall back to aliases, which may also map `#` prefixes
+        let subpath_import = specifier.starts_with('#');
+        if subpath_import {
+            if let Some(resolved) = self.resolve_package(specifier, kind, from_file, fs).await? {
+                return Ok(Some(resolved));
+            }
+        } else if self.is_external_package(specifier, from_file, fs).await? {
+            log::debug!("Skipping external package '{}'", specifier);
             return Ok(None);
         }
 
-        if self.is_asset_import(specifier) {
+        if self.is_asset_import(specifier) && !subpath_import {
             let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
             let asset_path = from_dir.join(specifier);
 
@@ -48,36 +99,52 @@ impl JsResolver {
             return Ok(None);
         }
 
-        if let Some(tsconfig) = self
-            .tsconfig_parser
-            .find_and_parse_config(from_file, fs)
-            .await?
-        {
-            if let Some(resolved_paths) = tsconfig.resolve_alias(specifier) {
-                for base_path in resolved_paths {
-                    if let Some(resolved) =
-                        self.resolve_file_with_extensions(&base_path, fs).await?
-                    {
-                        log::debug!(
-                            "Resolved alias '{}' to {} (from {})",
-                            specifier,
-                            resolved.display(),
-                            from_file.display()
-                        );
-                        return Ok(Some(ResolvedImport {
-                            path: resolved.absolutize()?.to_path_buf(),
-                            is_local: true,
-                            is_asset: false,
-                        }));
-                    }
+        let tsconfig_paths = match self.tsconfig_for(from_file, fs).await? {
+            Some(tsconfig) => tsconfig.resolve_alias(specifier),
+            None => None,
+        };
+        let bundler_paths = match self.aliases.config_for(from_file, fs).await {
+            Some(aliases) => aliases.resolve_alias(specifier),
+            None => None,
+        };
+
+        for resolved_paths in [tsconfig_paths, bundler_paths].into_iter().flatten() {
+            for base_path in &resolved_paths {
+                if let Some(resolved) = self.resolve_file_with_extensions(base_path, fs).await? {
+                    log::debug!(
+                        "Resolved alias '{}' to {} (from {})",
+                        specifier,
+                        resolved.display(),
+                        from_file.display()
+                    );
+                    return Ok(Some(ResolvedImport {
+                        path: resolved.absolutize()?.to_path_buf(),
+                        is_local: true,
+                        is_asset: false,
+                    }));
                 }
-
-                log::warn!(
-                    "Alias '{}' matched pattern but no file found (from {})",
-                    specifier,
-                    from_file.display()
-                );
             }
+
+            log::warn!(
+                "Alias '{}' matched pattern but no file found (from {})",
+                specifier,
+                from_file.display()
+            );
+        }
+
+        if subpath_import {
+            log::debug!(
+                "No package.json imports or alias matched '{}' (from {})",
+                specifier,
+                from_file.display()
+            );
+            return Ok(None);
+        }
+
+        if is_bare_specifier(specifier) {
+            // Bare specifiers may still lead back into the repository through
+            // package.json `exports` or a linked package
+            return self.resolve_package(specifier, kind, from_file, fs).await;
         }
 
         let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
@@ -101,6 +168,50 @@ impl JsResolver {
         }
     }
 
+    /// Resolves a bare or `#` specifier through package.json files. Only
+    /// packages whose real location is outside `node_modules` count as local.
+    async fn resolve_package(
+        &self,
+        specifier: &str,
+        kind: &ImportKind,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<ResolvedImport>> {
+        let candidates = self
+            .packages
+            .candidates(specifier, kind, from_file, fs)
+            .await;
+
+        for candidate in candidates {
+            let Some(resolved) = self.resolve_file_with_extensions(&candidate, fs).await? else {
+                continue;
+            };
+
+            let real_path = fs.canonicalize(&resolved).await.unwrap_or(resolved);
+            if is_in_node_modules(&real_path) {
+                return Ok(None);
+            }
+
+            log::debug!(
+                "Resolved package import '{}' to {} (from {})",
+                specifier,
+                real_path.display(),
+                from_file.display()
+            );
+            return Ok(Some(ResolvedImport {
+                path: real_path,
+                is_local: true,
+                is_asset: self.is_asset_import(specifier),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Whether `specifier` names a package that is never followed: a Node
+    /// builtin, a configured external or a dependency declared in the nearest
+    /// package.json that is not a workspace package. Other bare specifiers are
+    /// still looked up, so packages linked into the repository are followed.
     async fn is_external_package(
         &self,
         specifier: &str,
@@ -108,84 +219,63 @@ impl JsResolver {
         fs: &dyn FileSystemProvider,
     ) -> Result<bool> {
         // Quick check: obvious external packages
-        if specifier.starts_with("node_modules/") {
+        if specifier.starts_with("node_modules/") || specifier.starts_with("node:") {
             return Ok(true);
         }
 
-        // Check for known React/JS ecosystem externals first (performance optimization)
-        const KNOWN_EXTERNALS: &[&str] = &[
-            "react",
-            "react-dom",
-            "react-router",
-            "react-router-dom",
-            "redux",
-            "@reduxjs/",
-            "@mui/",
-            "@material-ui/",
-            "@emotion/",
-            "@testing-library/",
-            "axios",
-            "lodash",
-            "lodash-es",
-            "@tanstack/",
-            "framer-motion",
-            "@radix-ui/",
-            "next/",
-            "vue",
-            "svelte",
-            "@angular/",
-            "@types/",
-            "typescript",
-            "vite",
-            "webpack",
-            "rollup",
-        ];
-
-        if KNOWN_EXTERNALS
-            .iter()
-            .any(|&ext| specifier.starts_with(ext))
-        {
+        // Check if resolved path would enter node_modules
+        // This catches cases like: import something from '../../node_modules/...'
+        let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+        if is_in_node_modules(&from_dir.join(specifier)) {
             return Ok(true);
         }
 
         // Relative or absolute paths are local
-        if specifier.starts_with('.') || specifier.starts_with('/') {
+        if !is_bare_specifier(specifier) {
             return Ok(false);
         }
 
         // Check tsconfig path aliases
-        if let Some(tsconfig) = self
-            .tsconfig_parser
-            .find_and_parse_config(from_file, fs)
-            .await?
-        {
+        if let Some(tsconfig) = self.tsconfig_for(from_file, fs).await? {
             if tsconfig.resolve_alias(specifier).is_some() {
                 return Ok(false);
             }
         }
 
-        // Check common configured aliases
-        if self.is_configured_alias(specifier) {
-            return Ok(false);
+        // Check Vite/webpack/babel/Jest aliases
+        if let Some(aliases) = self.aliases.config_for(from_file, fs).await {
+            if aliases.resolve_alias(specifier).is_some() {
+                return Ok(false);
+            }
         }
 
-        // Check if resolved path would enter node_modules
-        // This catches cases like: import something from '../../node_modules/...'
-        let from_dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
-        let potential_path = from_dir.join(specifier);
+        let Some((name, _)) = split_package_name(specifier) else {
+            return Ok(false);
+        };
 
-        if potential_path.components().any(|c| {
-            if let std::path::Component::Normal(name) = c {
-                name == "node_modules"
-            } else {
-                false
-            }
-        }) {
+        if matches_package(&self.internals, name) {
+            return Ok(false);
+        }
+
+        if matches_package(&self.externals, name) || NODE_BUILTINS.contains(&name) {
             return Ok(true);
         }
 
-        // Default: bare specifiers without aliases are external packages
-        Ok(true)
+        let declared = self
+            .packages
+            .nearest(from_file, fs)
+            .await
+            .is_some_and(|package| package.depends_on(name));
+        if !declared {
+            return Ok(false);
+        }
+
+        // Workspace siblings may be declared with a plain version range
+        Ok(self
+            .packages
+            .workspace_package(name, from_file, fs)
+            .await
+            .is_none())
     }
 
     fn is_asset_import(&self, specifier: &str) -> bool {
@@ -225,24 +315,6 @@ impl JsResolver {
         false
     }
 
-    fn is_configured_alias(&self, specifier: &str) -> bool {
-        const COMMON_ALIASES: &[&str] = &[
-            "@/", // Common Vite/Next.js alias
-            "~/", // Common webpack alias
-            "@components/",
-            "@utils/",
-            "@assets/",
-            "@hooks/",
-            "@services/",
-            "@store/",
-            "@styles/",
-        ];
-
-        COMMON_ALIASES
-            .iter()
-            .any(|alias| specifier.starts_with(alias))
-    }
-
     async fn resolve_file_with_extensions(
         &self,
         path: &Path,
@@ -287,3 +359,149 @@ impl JsResolver {
         Ok(None)
     }
 }
+
+/// Node core modules, also importable with a `node:` prefix
+pub(super) const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+fn is_bare_specifier(specifier: &str) -> bool {
+    !specifier.starts_with('.') && !specifier.starts_with('/') && !specifier.starts_with('#')
+}
+
+/// Whether package `name` is listed in `patterns`, either exactly or through
+/// an `@scope/*` entry
+fn matches_package(patterns: &[String], name: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix("/*") {
+            Some(scope) => name
+                .strip_prefix(scope)
+                .is_some_and(|rest| rest.starts_with('/')),
+            None => pattern == name,
+        })
+}
+
+fn is_in_node_modules(path: &Path) -> bool {
+    path.components()
+        .any(|c| matches!(c, std::path::Component::Normal(name) if name == "node_modules"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+
+    #[tokio::test]
+    async fn test_workspace_sibling_with_version_range_is_followed() {
+        let fs = MemoryFileSystem::new([
+            ("/repo/package.json", r#"{"workspaces": ["packages/*"]}"#),
+            (
+                "/repo/packages/app/package.json",
+                r#"{"name": "app", "dependencies": {"@acme/ui": "^1.0.0", "react": "^18.0.0"}}"#,
+            ),
+            ("/repo/packages/app/src/main.ts", ""),
+            (
+                "/repo/packages/ui/package.json",
+                r#"{"name": "@acme/ui", "main": "src/index.ts"}"#,
+            ),
+            ("/repo/packages/ui/src/index.ts", ""),
+        ]);
+        let resolver = JsResolver::new();
+        let from = Path::new("/repo/packages/app/src/main.ts");
+
+        let ui = resolver
+            .resolve("@acme/ui", &ImportKind::EsModule, from, &fs)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ui.path, PathBuf::from("/repo/packages/ui/src/index.ts"));
+
+        let react = resolver
+            .resolve("react", &ImportKind::EsModule, from, &fs)
+            .await
+            .unwrap();
+        assert!(react.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subpath_imports_fall_back_to_aliases() {
+        let fs = MemoryFileSystem::new([
+            (
+                "/app/package.json",
+                r##"{"name": "app", "imports": {"#internal/*": "./src/internal/*.ts"}}"##,
+            ),
+            (
+                "/app/jest.config.js",
+                r#"module.exports = { moduleNameMapper: { '^#/(.*)$': '<rootDir>/src/$1' } };"#,
+            ),
+            ("/app/src/main.ts", ""),
+            ("/app/src/utils/math.ts", ""),
+            ("/app/src/internal/db.ts", ""),
+        ]);
+        let resolver = JsResolver::new();
+        let from = Path::new("/app/src/main.ts");
+        let resolve = |specifier: &'static str| {
+            let resolver = resolver.clone();
+            let fs = &fs;
+            async move {
+                resolver
+                    .resolve(specifier, &ImportKind::EsModule, from, fs)
+                    .await
+                    .unwrap()
+                    .map(|r| r.path)
+            }
+        };
+
+        assert_eq!(
+            resolve("#internal/db").await,
+            Some(PathBuf::from("/app/src/internal/db.ts"))
+        );
+        assert_eq!(
+            resolve("#/utils/math").await,
+            Some(PathBuf::from("/app/src/utils/math.ts"))
+        );
+        assert_eq!(resolve("#missing").await, None);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::core::language::{ImportKind, ImportStatement};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static SCRIPT_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<script\b([^>]*)>(.*?)</script\s*>").unwrap());
+static STYLE_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<style\b([^>]*?)(?:/>|>(.*?)</style\s*>)").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([A-Za-z_:][-A-Za-z0-9_:.]*)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>/]+)))?"#)
+        .unwrap()
+});
+static CSS_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"@import\s+(?:url\(\s*)?["']?([^"'()\s;]+)["']?\s*\)?"#).unwrap()
+});
+static CSS_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/").unwrap());
+
+/// A `<script>` block of a component
+pub struct ScriptBlock {
+    pub content: String,
+    /// Value of the `lang` attribute, if any
+    pub lang: Option<String>,
+    /// Value of the `src` attribute for external scripts
+    pub src: Option<String>,
+    /// 1-based line of the block content start in the component file
+    pub line: usize,
+    /// 0-based column of the block content start in the component file
+    pub column: usize,
+    pub raw_tag: String,
+}
+
+impl ScriptBlock {
+    /// Maps a position inside the block back to the component file
+    pub fn map_position(&self, line: usize, column: usize) -> (usize, usize) {
+        if line <= 1 {
+            (self.line, self.column + column)
+        } else {
+            (self.line + line - 1, column)
+        }
+    }
+}
+
+pub fn is_sfc_extension(extension: &str) -> bool {
+    matches!(extension, "vue" | "svelte")
+}
+
+pub fn extract_script_blocks(content: &str) -> Vec<ScriptBlock> {
+    SCRIPT_BLOCK
+        .captures_iter(content)
+        .map(|caps| {
+            let attrs = parse_attributes(caps.get(1).map_or("", |m| m.as_str()));
+            let body = caps.get(2).expect("script body group always participates");
+            let (line, column) = line_col(content, body.start());
+            let tag = caps.get(0).unwrap().as_str();
+            ScriptBlock {
+                content: body.as_str().to_string(),
+                lang: attrs.get("lang").cloned(),
+                src: attrs.get("src").cloned(),
+                line,
+                column,
+                raw_tag: tag[..tag.find('>').map_or(tag.len(), |i| i + 1)].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Collects `<style src>` references and `@import` rules inside `<style>` blocks
+/// as asset imports
+pub fn extract_style_imports(content: &str) -> Vec<ImportStatement> {
+    let mut imports = Vec::new();
+
+    for caps in STYLE_BLOCK.captures_iter(content) {
+        let tag = caps.get(0).unwrap();
+        let attrs = parse_attributes(caps.get(1).map_or("", |m| m.as_str()));
+        let lang = attrs.get("lang").map(String::as_str).unwrap_or("css");
+
+        if let Some(src) = attrs.get("src") {
+            let (line, column) = line_col(content, tag.start());
+            let raw = tag.as_str();
+            imports.push(ImportStatement {
+                specifier: normalize_style_specifier(src, lang),
+                kind: ImportKind::Asset,
+                line,
+                column,
+                raw: raw[..raw.find('>').map_or(raw.len(), |i| i + 1)].to_string(),
+                bindings: Vec::new(),
+            });
+        }
+
+        let Some(body) = caps.get(2) else {
+            continue;
+        };
+
+        // Blank out comments while keeping offsets stable
+        let stripped =
+            CSS_COMMENT.replace_all(body.as_str(), |c: &regex::Captures| " ".repeat(c[0].len()));
+
+        for import in CSS_IMPORT.captures_iter(&stripped) {
+            let specifier = &import[1];
+            if specifier.starts_with("http://")
+                || specifier.starts_with("https://")
+                || specifier.starts_with("//")
+            {
+                continue;
+            }
+
+            let whole = import.get(0).unwrap();
+            let (line, column) = line_col(content, body.start() + whole.start());
+            imports.push(ImportStatement {
+                specifier: normalize_style_specifier(specifier, lang),
+                kind: ImportKind::Asset,
+                line,
+                column,
+                raw: whole.as_str().trim_end().to_string(),
+                bindings: Vec::new(),
+            });
+        }
+    }
+
+    imports
+}
+
+/// CSS resolves bare `@import` targets relative to the stylesheet, and Sass
+/// lets the extension be omitted
+fn normalize_style_specifier(specifier: &str, lang: &str) -> String {
+    let mut normalized = if specifier.starts_with('.')
+        || specifier.starts_with('/')
+        || specifier.starts_with('~')
+        || specifier.starts_with('@')
+    {
+        specifier.to_string()
+    } else {
+        format!("./{}", specifier)
+    };
+
+    let file_name = normalized.rsplit('/').next().unwrap_or("");
+    if !file_name.contains('.') {
+        normalized.push('.');
+        normalized.push_str(lang);
+    }
+
+    normalized
+}
+
+fn parse_attributes(attrs: &str) -> HashMap<String, String> {
+    ATTRIBUTE
+        .captures_iter(attrs)
+        .map(|caps| {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            (caps[1].to_ascii_lowercase(), value)
+        })
+        .collect()
+}
+
+/// 1-based line and 0-based character column of a byte offset
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"<template>
+  <div>{{ msg }}</div>
+</template>
+
+<script setup lang="ts">
+import Button from './Button.vue'
+</script>
+
+<style lang="scss" scoped>
+/* @import 'ignored'; */
+@import 'variables';
+</style>
+<style src="./theme.css"></style>
+"#;
+
+    #[test]
+    fn test_extract_script_blocks() {
+        let blocks = extract_script_blocks(COMPONENT);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].lang.as_deref(), Some("ts"));
+        assert_eq!(blocks[0].map_position(2, 0), (6, 0));
+    }
+
+    #[test]
+    fn test_extract_style_imports() {
+        let imports = extract_style_imports(COMPONENT);
+        let specifiers: Vec<_> = imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(specifiers, vec!["./variables.scss", "./theme.css"]);
+        assert_eq!(imports[0].line, 11);
+        assert_eq!(imports[1].line, 13);
+    }
+}
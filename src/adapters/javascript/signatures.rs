@@ -0,0 +1,211 @@
+use super::parser::{parse_module, syntax_for_file};
+use anyhow::Result;
+use std::path::Path;
+use swc_common::{BytePos, SourceMap, Span, Spanned};
+use swc_ecma_ast::{
+    ArrowExpr, BlockStmtOrExpr, ClassProp, Constructor, Decl, ExportDefaultExpr, Expr, Function,
+    ModuleDecl, ModuleItem, Stmt, VarDeclarator,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Reduces a module to its declarations: imports, exports with function and
+/// method bodies elided, and every type, interface and enum. Leading comments
+/// of the kept statements are preserved.
+pub fn signatures(path: &Path, content: &str) -> Result<String> {
+    let (module, cm) = parse_module(path, content, syntax_for_file(path))?;
+
+    let mut output = String::new();
+    let mut previous_end = 0;
+    let mut previous_import = false;
+    for item in &module.body {
+        let span = item.span();
+        let (start, end) = (offset(&cm, span.lo), offset(&cm, span.hi));
+        let leading = content[previous_end..start].trim();
+        previous_end = end;
+
+        if !is_declaration(item) {
+            continue;
+        }
+
+        let mut elider = BodyElider {
+            cm: &cm,
+            content,
+            replacements: Vec::new(),
+        };
+        item.visit_with(&mut elider);
+        let text = splice(content, start, end, elider.replacements);
+
+        // Runs of imports stay together, everything else is spaced out
+        let is_import = matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_)));
+        if !output.is_empty() {
+            let grouped = is_import && previous_import && leading.is_empty();
+            output.push_str(if grouped { "\n" } else { "\n\n" });
+        }
+        previous_import = is_import;
+
+        if !leading.is_empty() {
+            output.push_str(leading);
+            output.push('\n');
+        }
+        output.push_str(&text);
+    }
+
+    Ok(output)
+}
+
+/// Statements that describe the module's shape rather than its behaviour
+fn is_declaration(item: &ModuleItem) -> bool {
+    match item {
+        ModuleItem::ModuleDecl(_) => true,
+        ModuleItem::Stmt(Stmt::Decl(decl)) => matches!(
+            decl,
+            Decl::TsInterface(_) | Decl::TsTypeAlias(_) | Decl::TsEnum(_) | Decl::TsModule(_)
+        ),
+        ModuleItem::Stmt(_) => false,
+    }
+}
+
+fn offset(cm: &SourceMap, pos: BytePos) -> usize {
+    cm.lookup_byte_offset(pos).pos.0 as usize
+}
+
+/// `content[start..end]` with each replacement span swapped for its text
+fn splice(
+    content: &str,
+    start: usize,
+    end: usize,
+    mut replacements: Vec<(usize, usize, &str)>,
+) -> String {
+    replacements.sort_by_key(|(from, _, _)| *from);
+
+    let mut text = String::new();
+    let mut cursor = start;
+    for (from, to, replacement) in replacements {
+        if from < cursor || to > end {
+            continue;
+        }
+        text.push_str(&content[cursor..from]);
+        text.push_str(replacement);
+        cursor = to;
+    }
+    text.push_str(&content[cursor..end]);
+    text
+}
+
+/// Collects the outermost function bodies and multi-line initializers of an
+/// item, to be replaced with placeholders
+struct BodyElider<'a> {
+    cm: &'a SourceMap,
+    content: &'a str,
+    replacements: Vec<(usize, usize, &'static str)>,
+}
+
+impl BodyElider<'_> {
+    fn replace(&mut self, span: Span, replacement: &'static str) {
+        let (from, to) = (offset(self.cm, span.lo), offset(self.cm, span.hi));
+        self.replacements.push((from, to, replacement));
+    }
+
+    /// Replaces values spanning several lines, which are rarely part of an API
+    fn replace_if_multiline(&mut self, span: Span) {
+        let (from, to) = (offset(self.cm, span.lo), offset(self.cm, span.hi));
+        if self.content[from..to].contains('\n') {
+            self.replacements.push((from, to, "…"));
+        }
+    }
+}
+
+impl Visit for BodyElider<'_> {
+    fn visit_function(&mut self, n: &Function) {
+        if let Some(body) = &n.body {
+            self.replace(body.span, "{ … }");
+        }
+    }
+
+    fn visit_constructor(&mut self, n: &Constructor) {
+        if let Some(body) = &n.body {
+            self.replace(body.span, "{ … }");
+        }
+    }
+
+    fn visit_arrow_expr(&mut self, n: &ArrowExpr) {
+        match &*n.body {
+            BlockStmtOrExpr::BlockStmt(block) => self.replace(block.span, "{ … }"),
+            BlockStmtOrExpr::Expr(expr) => self.replace_if_multiline(expr.span()),
+        }
+    }
+
+    fn visit_var_declarator(&mut self, n: &VarDeclarator) {
+        match n.init.as_deref() {
+            Some(Expr::Arrow(_) | Expr::Fn(_) | Expr::Class(_)) => n.visit_children_with(self),
+            Some(init) => self.replace_if_multiline(init.span()),
+            None => {}
+        }
+    }
+
+    fn visit_class_prop(&mut self, n: &ClassProp) {
+        if let Some(value) = &n.value {
+            self.replace_if_multiline(value.span());
+        }
+    }
+
+    fn visit_export_default_expr(&mut self, n: &ExportDefaultExpr) {
+        match &*n.expr {
+            Expr::Arrow(_) | Expr::Fn(_) | Expr::Class(_) => n.visit_children_with(self),
+            expr => self.replace_if_multiline(expr.span()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures_elide_bodies() {
+        let content = r#"import { pad } from './pad';
+
+const cache = new Map();
+
+/** Formats a number */
+export function format(n: number): string {
+  return pad(n);
+}
+
+export class Formatter {
+  private width = 2;
+  constructor(width: number) {
+    this.width = width;
+  }
+  run(n: number): string {
+    return format(n);
+  }
+}
+
+export const parse = (s: string): number => {
+  return Number(s);
+};
+
+interface Options { width: number }
+"#;
+        let result = signatures(Path::new("/src/format.ts"), content).unwrap();
+
+        assert_eq!(
+            result,
+            r#"import { pad } from './pad';
+
+/** Formats a number */
+export function format(n: number): string { … }
+
+export class Formatter {
+  private width = 2;
+  constructor(width: number) { … }
+  run(n: number): string { … }
+}
+
+export const parse = (s: string): number => { … };
+
+interface Options { width: number }"#
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use super::parser::{parse_module, syntax_for_file};
+use anyhow::Result;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+use swc_common::{Span, Spanned};
+use swc_ecma_ast::{
+    Decl, DefaultDecl, ExportSpecifier, Ident, ImportSpecifier, ModuleDecl, ModuleExportName,
+    ModuleItem, Pat, Stmt, TsModuleName,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// Extensions [`slice_module`] understands
+pub const SLICEABLE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// A module reduced to the declarations its importers need
+#[derive(Debug)]
+pub struct SlicedModule {
+    pub content: String,
+    /// Names of the top-level declarations that were left out
+    pub omitted: Vec<String>,
+    /// Identifiers the remaining code references, including imported names
+    pub referenced: HashSet<String>,
+}
+
+/// A top-level statement and the names it connects
+struct Item {
+    span: Span,
+    /// Top-level bindings it declares
+    declares: Vec<String>,
+    /// Export names it provides
+    exports: Vec<String>,
+    /// Identifiers used anywhere in it
+    references: HashSet<String>,
+    /// Statements with side effects, kept regardless of use
+    always: bool,
+}
+
+/// Keeps the top-level declarations of `content` that provide one of `used`,
+/// plus every local declaration and import they reference and all statements
+/// with side effects. Each run of left-out declarations is replaced with a
+/// marker comment.
+///
+/// Returns `None` when nothing can be left out.
+pub fn slice_module(
+    path: &Path,
+    content: &str,
+    used: &BTreeSet<String>,
+) -> Result<Option<SlicedModule>> {
+    let (module, cm) = parse_module(path, content, syntax_for_file(path))?;
+    let items: Vec<Item> = module.body.iter().map(item_for).collect();
+
+    let mut kept: Vec<bool> = items
+        .iter()
+        .map(|item| item.always || item.exports.iter().any(|e| used.contains(e)))
+        .collect();
+
+    let mut pending: Vec<&str> = items
+        .iter()
+        .zip(&kept)
+        .filter(|(_, kept)| **kept)
+        .flat_map(|(item, _)| item.references.iter().map(String::as_str))
+        .collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    while let Some(name) = pending.pop() {
+        if !seen.insert(name) {
+            continue;
+        }
+        for (i, item) in items.iter().enumerate() {
+            if !kept[i] && item.declares.iter().any(|d| d == name) {
+                kept[i] = true;
+                pending.extend(item.references.iter().map(String::as_str));
+            }
+        }
+    }
+
+    if kept.iter().all(|k| *k) {
+        return Ok(None);
+    }
+
+    let offset = |pos| cm.lookup_byte_offset(pos).pos.0 as usize;
+    let mut output = String::new();
+    let mut omitted = Vec::new();
+    let mut run: Vec<String> = Vec::new();
+    let mut cursor = items.first().map_or(0, |item| offset(item.span.lo));
+    output.push_str(&content[..cursor]);
+
+    for (item, kept) in items.iter().zip(&kept) {
+        // Each item owns the whitespace and comments before it
+        let end = offset(item.span.hi);
+        if *kept {
+            flush_marker(&mut output, &mut run);
+            output.push_str(&content[cursor..end]);
+        } else {
+            let names = if item.declares.is_empty() {
+                &item.exports
+            } else {
+                &item.declares
+            };
+            run.extend(names.iter().cloned());
+            omitted.extend(names.iter().cloned());
+        }
+        cursor = end;
+    }
+    flush_marker(&mut output, &mut run);
+    output.push_str(&content[cursor..]);
+
+    Ok(Some(SlicedModule {
+        content: output.trim_start_matches('\n').to_string(),
+        omitted,
+        referenced: seen.into_iter().map(str::to_string).collect(),
+    }))
+}
+
+fn flush_marker(output: &mut String, run: &mut Vec<String>) {
+    if run.is_empty() {
+        return;
+    }
+    if !output.is_empty() {
+        output.push_str("\n\n");
+    }
+    output.push_str(&format!("// [packlet] unused, omitted: {}", run.join(", ")));
+    run.clear();
+}
+
+fn item_for(item: &ModuleItem) -> Item {
+    let mut result = Item {
+        span: item.span(),
+        declares: Vec::new(),
+        exports: Vec::new(),
+        references: references(item),
+        always: false,
+    };
+
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+            result.always = import.specifiers.is_empty();
+            result.declares = import
+                .specifiers
+                .iter()
+                .map(|specifier| match specifier {
+                    ImportSpecifier::Named(named) => named.local.sym.to_string(),
+                    ImportSpecifier::Default(default) => default.local.sym.to_string(),
+                    ImportSpecifier::Namespace(namespace) => namespace.local.sym.to_string(),
+                })
+                .collect();
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+            result.declares = decl_names(&export.decl);
+            result.exports = result.declares.clone();
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => {
+            result.exports = export
+                .specifiers
+                .iter()
+                .map(|specifier| match specifier {
+                    ExportSpecifier::Named(named) => {
+                        export_name(named.exported.as_ref().unwrap_or(&named.orig))
+                    }
+                    ExportSpecifier::Namespace(namespace) => export_name(&namespace.name),
+                    ExportSpecifier::Default(default) => default.exported.sym.to_string(),
+                })
+                .collect();
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+            result.exports = vec!["default".to_string()];
+            let ident = match &export.decl {
+                DefaultDecl::Class(class) => class.ident.as_ref(),
+                DefaultDecl::Fn(function) => function.ident.as_ref(),
+                DefaultDecl::TsInterfaceDecl(interface) => Some(&interface.id),
+            };
+            result.declares = ident.map(|i| i.sym.to_string()).into_iter().collect();
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(_)) => {
+            result.exports = vec!["default".to_string()];
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import)) => {
+            result.declares = vec![import.id.sym.to_string()];
+            if import.is_export {
+                result.exports = result.declares.clone();
+            }
+        }
+        // `export *`, `export =` and `export as namespace` can't be narrowed
+        ModuleItem::ModuleDecl(_) => result.always = true,
+        ModuleItem::Stmt(Stmt::Decl(decl)) => {
+            result.declares = decl_names(decl);
+            // `declare global { ... }` and `declare module "x"` augment other scopes
+            result.always = result.declares.is_empty();
+        }
+        ModuleItem::Stmt(_) => result.always = true,
+    }
+
+    result
+}
+
+fn decl_names(decl: &Decl) -> Vec<String> {
+    match decl {
+        Decl::Class(class) => vec![class.ident.sym.to_string()],
+        Decl::Fn(function) => vec![function.ident.sym.to_string()],
+        Decl::Var(var) => var.decls.iter().flat_map(|d| pat_names(&d.name)).collect(),
+        Decl::Using(using) => using
+            .decls
+            .iter()
+            .flat_map(|d| pat_names(&d.name))
+            .collect(),
+        Decl::TsInterface(interface) => vec![interface.id.sym.to_string()],
+        Decl::TsTypeAlias(alias) => vec![alias.id.sym.to_string()],
+        Decl::TsEnum(ts_enum) => vec![ts_enum.id.sym.to_string()],
+        Decl::TsModule(module) => match &module.id {
+            TsModuleName::Ident(ident) => vec![ident.sym.to_string()],
+            TsModuleName::Str(_) => Vec::new(),
+        },
+    }
+}
+
+/// Names bound by a declaration pattern such as `{ a, b: [c] }`
+fn pat_names(pat: &Pat) -> Vec<String> {
+    struct Collector(Vec<String>);
+    impl Visit for Collector {
+        fn visit_binding_ident(&mut self, n: &swc_ecma_ast::BindingIdent) {
+            self.0.push(n.id.sym.to_string());
+        }
+        // Default values are expressions, not bindings
+        fn visit_expr(&mut self, _: &swc_ecma_ast::Expr) {}
+    }
+
+    let mut collector = Collector(Vec::new());
+    pat.visit_with(&mut collector);
+    collector.0
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    name.atom().to_string()
+}
+
+/// Every identifier in an item. Shadowing is ignored, so this can only keep
+/// too much, never too little.
+fn references(item: &ModuleItem) -> HashSet<String> {
+    struct Collector(HashSet<String>);
+    impl Visit for Collector {
+        fn visit_ident(&mut self, n: &Ident) {
+            self.0.insert(n.sym.to_string());
+        }
+    }
+
+    let mut collector = Collector(HashSet::new());
+    item.visit_with(&mut collector);
+    collector.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_keeps_used_exports_and_their_helpers() {
+        let content = r#"import { pad } from './pad';
+import { clamp } from './numbers';
+
+const PREFIX = '#';
+
+export function format(n: number) {
+  return PREFIX + pad(n);
+}
+
+export function parse(s: string) {
+  return clamp(Number(s), 0, 10);
+}
+
+registerFormatter(format);
+"#;
+        let used = BTreeSet::from(["format".to_string()]);
+        let sliced = slice_module(Path::new("/src/format.ts"), content, &used)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(sliced.omitted, vec!["clamp", "parse"]);
+        assert!(sliced.content.contains("import { pad } from './pad';"));
+        assert!(sliced.content.contains("const PREFIX = '#';"));
+        assert!(sliced.content.contains("registerFormatter(format);"));
+        assert!(!sliced.content.contains("Number(s)"));
+        assert!(sliced.referenced.contains("pad"));
+        assert!(!sliced.referenced.contains("clamp"));
+    }
+}
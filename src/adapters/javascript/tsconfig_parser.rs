@@ -257,7 +257,7 @@ impl TsConfigParser {
                 if let Some(&next_ch) = chars.peek() {
                     if next_ch == '/' {
                         chars.next();
-                        while let Some(ch) = chars.next() {
+                        for ch in chars.by_ref() {
                             if ch == '\n' {
                                 result.push('\n');
                                 break;
@@ -266,7 +266,7 @@ impl TsConfigParser {
                     } else if next_ch == '*' {
                         chars.next();
                         let mut prev_ch = ' ';
-                        while let Some(ch) = chars.next() {
+                        for ch in chars.by_ref() {
                             if prev_ch == '*' && ch == '/' {
                                 break;
                             }
@@ -0,0 +1,237 @@
+use crate::core::fs::FileSystemProvider;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Packages of an npm/yarn/pnpm workspace, by package name
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub packages: HashMap<String, PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WorkspacesField {
+    Patterns(Vec<String>),
+    /// Yarn's `{ "packages": [...], "nohoist": [...] }` form
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct RootPackageJson {
+    workspaces: Option<WorkspacesField>,
+}
+
+#[derive(Deserialize)]
+struct PackageName {
+    name: Option<String>,
+}
+
+impl Workspace {
+    /// Maps every package matched by the workspace `patterns` to its directory
+    pub async fn load(root: &Path, patterns: &[String], fs: &dyn FileSystemProvider) -> Self {
+        let mut packages = HashMap::new();
+        let (excluded, included): (Vec<&String>, Vec<&String>) =
+            patterns.iter().partition(|p| p.starts_with('!'));
+        let excluded: Vec<glob::Pattern> = excluded
+            .iter()
+            .filter_map(|p| glob::Pattern::new(&root.join(&p[1..]).to_string_lossy()).ok())
+            .collect();
+
+        for pattern in included {
+            for dir in matching_dirs(root, pattern, fs).await {
+                if excluded.iter().any(|p| p.matches_path(&dir)) {
+                    continue;
+                }
+                let Ok(content) = fs.read_file(&dir.join("package.json")).await else {
+                    continue;
+                };
+                if let Ok(PackageName { name: Some(name) }) = serde_json::from_str(&content) {
+                    packages.insert(name, dir);
+                }
+            }
+        }
+
+        log::debug!(
+            "Found {} workspace packages under {}",
+            packages.len(),
+            root.display()
+        );
+
+        Self { packages }
+    }
+}
+
+/// Directories under `root` matching a workspace glob such as `packages/*` or
+/// `apps/**`, listed through `fs`. `node_modules` and hidden directories are
+/// never entered.
+async fn matching_dirs(root: &Path, pattern: &str, fs: &dyn FileSystemProvider) -> Vec<PathBuf> {
+    let segments: Vec<&str> = pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    let mut matches = Vec::new();
+    // Directories still to match against the segment at the given index
+    let mut pending = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, index)) = pending.pop() {
+        let Some(segment) = segments.get(index) else {
+            if dir != root && !matches.contains(&dir) {
+                matches.push(dir);
+            }
+            continue;
+        };
+
+        if *segment == "**" {
+            pending.push((dir.clone(), index + 1));
+        } else if !segment.contains(['*', '?', '[']) {
+            let child = dir.join(segment);
+            if fs.is_directory(&child).await {
+                pending.push((child, index + 1));
+            }
+            continue;
+        }
+
+        let Ok(segment_pattern) = glob::Pattern::new(segment) else {
+            log::warn!("Invalid workspace pattern: {}", pattern);
+            return Vec::new();
+        };
+        let children = match fs.read_dir(&dir).await {
+            Ok(children) => children,
+            Err(e) => {
+                log::debug!("Could not expand workspace pattern {}: {}", pattern, e);
+                continue;
+            }
+        };
+        for child in children {
+            let Some(name) = child.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if name == "node_modules" || name.starts_with('.') || !fs.is_directory(&child).await {
+                continue;
+            }
+            if *segment == "**" {
+                pending.push((child, index));
+            } else if segment_pattern.matches(name) {
+                pending.push((child, index + 1));
+            }
+        }
+    }
+
+    matches.sort();
+    matches
+}
+
+/// Workspace globs declared in `dir`, if it is a workspace root
+pub async fn workspace_patterns(dir: &Path, fs: &dyn FileSystemProvider) -> Option<Vec<String>> {
+    let pnpm_workspace = dir.join("pnpm-workspace.yaml");
+    if fs.exists(&pnpm_workspace).await {
+        let content = fs.read_file(&pnpm_workspace).await.ok()?;
+        return Some(parse_pnpm_workspace(&content));
+    }
+
+    let package_json = dir.join("package.json");
+    if !fs.exists(&package_json).await {
+        return None;
+    }
+    let content = fs.read_file(&package_json).await.ok()?;
+    match serde_json::from_str::<RootPackageJson>(&content)
+        .ok()?
+        .workspaces?
+    {
+        WorkspacesField::Patterns(patterns) => Some(patterns),
+        WorkspacesField::Object { packages } => Some(packages),
+    }
+}
+
+/// Reads the `packages` list of a `pnpm-workspace.yaml`. Only the block list
+/// form pnpm documents is supported.
+fn parse_pnpm_workspace(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or("").trim_end();
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_packages = line.trim() == "packages:";
+            continue;
+        }
+
+        if in_packages {
+            if let Some(item) = line.trim().strip_prefix('-') {
+                let item = item.trim().trim_matches(|c| c == '"' || c == '\'');
+                if !item.is_empty() {
+                    patterns.push(item.to_string());
+                }
+            }
+        }
+    }
+
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+
+    #[tokio::test]
+    async fn test_load_matches_patterns_through_fs() {
+        let fs = MemoryFileSystem::new([
+            ("/repo/packages/ui/package.json", r#"{"name": "@acme/ui"}"#),
+            (
+                "/repo/packages/api/package.json",
+                r#"{"name": "@acme/api"}"#,
+            ),
+            (
+                "/repo/packages/api/node_modules/dep/package.json",
+                r#"{"name": "dep"}"#,
+            ),
+            ("/repo/apps/web/site/package.json", r#"{"name": "site"}"#),
+            (
+                "/repo/apps/web/site/test/fixture/package.json",
+                r#"{"name": "fixture"}"#,
+            ),
+            ("/repo/tools/readme.md", ""),
+        ]);
+        let patterns = vec![
+            "packages/*".to_string(),
+            "apps/**".to_string(),
+            "!**/test/**".to_string(),
+        ];
+
+        let workspace = Workspace::load(Path::new("/repo"), &patterns, &fs).await;
+        let mut names: Vec<&str> = workspace.packages.keys().map(String::as_str).collect();
+        names.sort();
+
+        assert_eq!(names, vec!["@acme/api", "@acme/ui", "site"]);
+        assert_eq!(
+            workspace.packages["site"],
+            PathBuf::from("/repo/apps/web/site")
+        );
+    }
+
+    #[test]
+    fn test_parse_pnpm_workspace() {
+        let content = r#"
+packages:
+  # all packages in direct subdirs of packages/
+  - 'packages/*'
+  - "apps/**"
+  - '!**/test/**'
+catalog:
+  react: ^18.2.0
+"#;
+        assert_eq!(
+            parse_pnpm_workspace(content),
+            vec!["packages/*", "apps/**", "!**/test/**"]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+/// Start of every line in a text, for turning character indices into the
+/// line and column of an [`ImportStatement`](crate::core::language::ImportStatement)
+pub struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(chars: &[char]) -> Self {
+        let starts = std::iter::once(0)
+            .chain(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c == '\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+        Self { starts }
+    }
+
+    /// 1-based line and 0-based column of the character at `index`
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&start| start <= index);
+        (line, index - self.starts[line - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let chars: Vec<char> = "ab\n\néx\n".chars().collect();
+        let index = LineIndex::new(&chars);
+
+        assert_eq!(index.position(0), (1, 0));
+        assert_eq!(index.position(2), (1, 2));
+        assert_eq!(index.position(3), (2, 0));
+        assert_eq!(index.position(5), (3, 1));
+        assert_eq!(index.position(7), (4, 0));
+    }
+}
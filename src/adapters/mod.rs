@@ -1 +1,7 @@
+pub mod css;
+pub mod go;
+pub mod html;
 pub mod javascript;
+pub(crate) mod line_index;
+pub mod python;
+pub mod rust;
@@ -0,0 +1,59 @@
+mod parser;
+mod resolver;
+
+use crate::config::PythonConfig;
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
+use anyhow::Result;
+use async_trait::async_trait;
+use parser::PyParser;
+use resolver::PyResolver;
+use std::path::Path;
+
+#[derive(Clone, Default)]
+pub struct PythonAdapter {
+    parser: PyParser,
+    resolver: PyResolver,
+}
+
+impl PythonAdapter {
+    pub fn new() -> Self {
+        Self {
+            parser: PyParser::new(),
+            resolver: PyResolver::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: &PythonConfig) -> Self {
+        self.resolver = self.resolver.with_config(config);
+        self
+    }
+}
+
+#[async_trait]
+impl LanguageAdapter for PythonAdapter {
+    fn name(&self) -> &str {
+        "Python"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["py", "pyi"]
+    }
+
+    async fn parse_imports(
+        &self,
+        _file_path: &Path,
+        content: &str,
+        _context: &AnalysisContext,
+    ) -> Result<Vec<ImportStatement>> {
+        Ok(self.parser.parse(content))
+    }
+
+    async fn resolve_import(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Option<ResolvedImport>> {
+        self.resolver.resolve(import, from_file, &*context.fs).await
+    }
+}
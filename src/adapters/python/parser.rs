@@ -0,0 +1,432 @@
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Calls that import a module by name at runtime, when the name is a whole
+/// literal argument
+static DYNAMIC_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:importlib\.import_module|__import__)\(\s*[rbuRBU]?["']([\w.]+)["']\s*[,)]"#)
+        .unwrap()
+});
+
+static TYPE_CHECKING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^if\s+(?:typing\.)?TYPE_CHECKING\s*:$").unwrap());
+
+/// Kind of the static imports Python files produce
+pub const PYTHON_IMPORT: &str = "python";
+
+/// A complete statement with continuation lines, bracketed line breaks and
+/// comments folded away
+struct Statement {
+    text: String,
+    line: usize,
+    column: usize,
+    /// Indentation of the line the statement starts on
+    indent: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PyParser;
+
+impl PyParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Finds `import` and `from ... import` statements, wherever they are
+    /// nested, plus `importlib.import_module`/`__import__` calls with literal
+    /// module names. Imports inside `if TYPE_CHECKING:` are type-only.
+    pub fn parse(&self, content: &str) -> Vec<ImportStatement> {
+        let mut imports = Vec::new();
+        // Indentation of the enclosing `if TYPE_CHECKING:` block, if any
+        let mut type_checking: Option<usize> = None;
+
+        for statement in statements(content) {
+            if type_checking.is_some_and(|indent| statement.indent <= indent) {
+                type_checking = None;
+            }
+            let text = statement.text.trim();
+            if TYPE_CHECKING.is_match(text) {
+                type_checking = Some(statement.indent);
+                continue;
+            }
+            let type_only = type_checking.is_some();
+
+            if let Some(rest) = keyword(text, "import") {
+                imports.extend(import_names(rest, type_only, &statement));
+            } else if let Some(rest) = keyword(text, "from") {
+                imports.extend(from_import(rest, type_only, &statement));
+            } else {
+                for captures in DYNAMIC_IMPORT.captures_iter(text) {
+                    imports.push(ImportStatement {
+                        specifier: captures[1].to_string(),
+                        kind: ImportKind::Dynamic,
+                        line: statement.line,
+                        column: statement.column,
+                        raw: captures[0].to_string(),
+                        bindings: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        imports
+    }
+}
+
+/// The rest of `text` after a leading `keyword` and whitespace
+fn keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(keyword)?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim_start())
+}
+
+fn statement_import(
+    statement: &Statement,
+    specifier: String,
+    type_only: bool,
+    bindings: Vec<ImportBinding>,
+) -> ImportStatement {
+    ImportStatement {
+        specifier,
+        kind: if type_only {
+            ImportKind::TypeOnly
+        } else {
+            ImportKind::Custom(PYTHON_IMPORT.to_string())
+        },
+        line: statement.line,
+        column: statement.column,
+        raw: statement.text.trim().to_string(),
+        bindings,
+    }
+}
+
+/// `import a.b, c as d`
+fn import_names(rest: &str, type_only: bool, statement: &Statement) -> Vec<ImportStatement> {
+    rest.split(',')
+        .filter_map(|name| {
+            let (module, alias) = split_alias(name)?;
+            // `import a.b` binds `a`
+            let local = alias.unwrap_or_else(|| module.split('.').next().unwrap_or(module));
+            Some(statement_import(
+                statement,
+                module.to_string(),
+                type_only,
+                vec![ImportBinding::Namespace {
+                    local: local.to_string(),
+                    type_only,
+                }],
+            ))
+        })
+        .collect()
+}
+
+/// `from .a import (b, c as d)`. Every name gets its own statement, since it
+/// may be a submodule rather than an attribute of the module.
+fn from_import(rest: &str, type_only: bool, statement: &Statement) -> Vec<ImportStatement> {
+    let Some((module, names)) = rest.split_once(char::is_whitespace) else {
+        return Vec::new();
+    };
+    let Some(names) = keyword(names.trim_start(), "import") else {
+        return Vec::new();
+    };
+    let names = names
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    if !is_module_path(module) {
+        return Vec::new();
+    }
+
+    if names == "*" {
+        return vec![statement_import(
+            statement,
+            module.to_string(),
+            type_only,
+            Vec::new(),
+        )];
+    }
+
+    names
+        .split(',')
+        .filter_map(|name| {
+            let (imported, alias) = split_alias(name)?;
+            Some(statement_import(
+                statement,
+                module.to_string(),
+                type_only,
+                vec![ImportBinding::Named {
+                    imported: imported.to_string(),
+                    local: alias.unwrap_or(imported).to_string(),
+                    type_only,
+                }],
+            ))
+        })
+        .collect()
+}
+
+/// Splits `name as alias`, skipping empty entries left by trailing commas
+fn split_alias(name: &str) -> Option<(&str, Option<&str>)> {
+    let mut parts = name.split_whitespace();
+    let name = parts.next()?;
+    let alias = match parts.next() {
+        Some("as") => parts.next(),
+        _ => None,
+    };
+    Some((name, alias))
+}
+
+/// Leading dots followed by an optional dotted name
+fn is_module_path(module: &str) -> bool {
+    let name = module.trim_start_matches('.');
+    (name.is_empty() && !module.is_empty())
+        || name
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// Splits `content` into statements, joining lines continued with a
+/// backslash or inside brackets and dropping comments. String literals are
+/// kept so literal module names can be matched.
+fn statements(content: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start = (1, 0, 0);
+    let mut line = 1;
+    let mut column = 0;
+    let mut depth = 0usize;
+    let mut at_line_start = true;
+    let mut indent = 0;
+
+    let mut finish = |current: &mut String, start: (usize, usize, usize)| {
+        if !current.trim().is_empty() {
+            statements.push(Statement {
+                text: std::mem::take(current),
+                line: start.0,
+                column: start.1,
+                indent: start.2,
+            });
+        }
+        current.clear();
+    };
+
+    let chars: Vec<char> = content.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if at_line_start {
+            if c == ' ' || c == '\t' {
+                indent += 1;
+                column += 1;
+                i += 1;
+                continue;
+            }
+            at_line_start = false;
+        }
+        if current.is_empty() && !c.is_whitespace() && c != '#' {
+            start = (line, column, indent);
+        }
+
+        match c {
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '\\' if chars.get(i + 1) == Some(&'\n') => {
+                current.push(' ');
+                line += 1;
+                column = 0;
+                i += 2;
+                continue;
+            }
+            '"' | '\'' => {
+                let triple = chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c);
+                let quote_len = if triple { 3 } else { 1 };
+                current.extend(&chars[i..i + quote_len]);
+                i += quote_len;
+                column += quote_len;
+                while i < chars.len() {
+                    let s = chars[i];
+                    if s == '\\' && i + 1 < chars.len() {
+                        current.push(s);
+                        current.push(chars[i + 1]);
+                        if chars[i + 1] == '\n' {
+                            line += 1;
+                            column = 0;
+                        } else {
+                            column += 2;
+                        }
+                        i += 2;
+                        continue;
+                    }
+                    if s == c
+                        && (!triple
+                            || (chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c)))
+                    {
+                        current.extend(&chars[i..i + quote_len]);
+                        i += quote_len;
+                        column += quote_len;
+                        break;
+                    }
+                    if s == '\n' {
+                        line += 1;
+                        column = 0;
+                        // An unterminated single-quoted string ends at the line
+                        if !triple {
+                            break;
+                        }
+                        current.push(' ');
+                    } else {
+                        current.push(s);
+                        column += 1;
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                finish(&mut current, start);
+                i += 1;
+                column += 1;
+                continue;
+            }
+            '\n' => {
+                line += 1;
+                column = 0;
+                i += 1;
+                if depth == 0 {
+                    finish(&mut current, start);
+                    at_line_start = true;
+                    indent = 0;
+                } else {
+                    current.push(' ');
+                }
+                continue;
+            }
+            // Whitespace between statements on one line
+            c if c.is_whitespace() && current.is_empty() => {
+                column += 1;
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        current.push(c);
+        column += 1;
+        i += 1;
+    }
+    finish(&mut current, start);
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifiers(imports: &[ImportStatement]) -> Vec<(&str, usize, usize)> {
+        imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.line, i.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_binds_first_segment_or_alias() {
+        let imports = PyParser::new().parse("import os.path, app.models as models\n");
+
+        assert_eq!(
+            specifiers(&imports),
+            vec![("os.path", 1, 0), ("app.models", 1, 0)]
+        );
+        assert_eq!(
+            imports[0].bindings,
+            vec![ImportBinding::Namespace {
+                local: "os".into(),
+                type_only: false,
+            }]
+        );
+        assert_eq!(
+            imports[1].bindings,
+            vec![ImportBinding::Namespace {
+                local: "models".into(),
+                type_only: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_from_import_spanning_lines() {
+        let content = "from ..core.utils import (\n    slugify,  # comment\n    Timer as T,\n)\nfrom . import \\\n    views\n";
+        let imports = PyParser::new().parse(content);
+
+        assert_eq!(
+            specifiers(&imports),
+            vec![("..core.utils", 1, 0), ("..core.utils", 1, 0), (".", 5, 0)]
+        );
+        assert_eq!(
+            imports[1].bindings,
+            vec![ImportBinding::Named {
+                imported: "Timer".into(),
+                local: "T".into(),
+                type_only: false,
+            }]
+        );
+        assert_eq!(
+            imports[2].bindings,
+            vec![ImportBinding::Named {
+                imported: "views".into(),
+                local: "views".into(),
+                type_only: false,
+            }]
+        );
+        // A wildcard import binds nothing
+        assert!(PyParser::new().parse("from pkg import *")[0]
+            .bindings
+            .is_empty());
+    }
+
+    #[test]
+    fn test_type_checking_block_ends_at_dedent() {
+        let content = "if typing.TYPE_CHECKING:\n    from .types import Config\n    if True:\n        import models\nimport json\n";
+        let kinds: Vec<(String, bool)> = PyParser::new()
+            .parse(content)
+            .iter()
+            .map(|i| (i.specifier.clone(), matches!(i.kind, ImportKind::TypeOnly)))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (".types".to_string(), true),
+                ("models".to_string(), true),
+                ("json".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strings_and_comments_hold_no_imports() {
+        let content = "\"\"\"Docs\nimport os\n\"\"\"\n# import sys\nx = 'from a import b'\n";
+        assert!(PyParser::new().parse(content).is_empty());
+    }
+
+    #[test]
+    fn test_nested_and_dynamic_imports() {
+        let content = "def load(name):\n    import json; plugin = importlib.import_module(\"app.plugins\")\n    __import__('app.' + name)\n";
+        let imports = PyParser::new().parse(content);
+
+        assert_eq!(
+            specifiers(&imports),
+            vec![("json", 2, 4), ("app.plugins", 2, 17)]
+        );
+        assert!(matches!(imports[1].kind, ImportKind::Dynamic));
+    }
+}
@@ -0,0 +1,572 @@
+use crate::config::PythonConfig;
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportBinding, ImportStatement, ResolvedImport};
+use anyhow::Result;
+use dashmap::DashMap;
+use path_absolutize::Absolutize;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Files marking the root of a Python project
+const PROJECT_FILES: &[&str] = &["pyproject.toml", "setup.py", "setup.cfg"];
+
+/// Directories holding installed packages, which are never followed
+const INSTALL_DIRS: &[&str] = &["site-packages", "dist-packages", ".venv", "venv", ".tox"];
+
+/// Top-level modules of the standard library
+pub(super) const STDLIB_MODULES: &[&str] = &[
+    "__future__",
+    "_thread",
+    "abc",
+    "aifc",
+    "argparse",
+    "array",
+    "ast",
+    "asynchat",
+    "asyncio",
+    "asyncore",
+    "atexit",
+    "audioop",
+    "base64",
+    "bdb",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "calendar",
+    "cgi",
+    "cgitb",
+    "chunk",
+    "cmath",
+    "cmd",
+    "code",
+    "codecs",
+    "codeop",
+    "collections",
+    "colorsys",
+    "compileall",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "copyreg",
+    "cProfile",
+    "crypt",
+    "csv",
+    "ctypes",
+    "curses",
+    "dataclasses",
+    "datetime",
+    "dbm",
+    "decimal",
+    "difflib",
+    "dis",
+    "doctest",
+    "email",
+    "encodings",
+    "ensurepip",
+    "enum",
+    "errno",
+    "faulthandler",
+    "fcntl",
+    "filecmp",
+    "fileinput",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "getopt",
+    "getpass",
+    "gettext",
+    "glob",
+    "graphlib",
+    "grp",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "idlelib",
+    "imaplib",
+    "imghdr",
+    "imp",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "lib2to3",
+    "linecache",
+    "locale",
+    "logging",
+    "lzma",
+    "mailbox",
+    "mailcap",
+    "marshal",
+    "math",
+    "mimetypes",
+    "mmap",
+    "modulefinder",
+    "msvcrt",
+    "multiprocessing",
+    "netrc",
+    "nis",
+    "nntplib",
+    "numbers",
+    "operator",
+    "optparse",
+    "os",
+    "ossaudiodev",
+    "pathlib",
+    "pdb",
+    "pickle",
+    "pickletools",
+    "pipes",
+    "pkgutil",
+    "platform",
+    "plistlib",
+    "poplib",
+    "posix",
+    "pprint",
+    "profile",
+    "pstats",
+    "pty",
+    "pwd",
+    "py_compile",
+    "pyclbr",
+    "pydoc",
+    "queue",
+    "quopri",
+    "random",
+    "re",
+    "readline",
+    "reprlib",
+    "resource",
+    "rlcompleter",
+    "runpy",
+    "sched",
+    "secrets",
+    "select",
+    "selectors",
+    "shelve",
+    "shlex",
+    "shutil",
+    "signal",
+    "site",
+    "smtpd",
+    "smtplib",
+    "sndhdr",
+    "socket",
+    "socketserver",
+    "spwd",
+    "sqlite3",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "stringprep",
+    "struct",
+    "subprocess",
+    "sunau",
+    "symtable",
+    "sys",
+    "sysconfig",
+    "syslog",
+    "tabnanny",
+    "tarfile",
+    "telnetlib",
+    "tempfile",
+    "termios",
+    "textwrap",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "tomllib",
+    "trace",
+    "traceback",
+    "tracemalloc",
+    "tty",
+    "turtle",
+    "turtledemo",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uu",
+    "uuid",
+    "venv",
+    "warnings",
+    "wave",
+    "weakref",
+    "webbrowser",
+    "winreg",
+    "winsound",
+    "wsgiref",
+    "xdrlib",
+    "xml",
+    "xmlrpc",
+    "zipapp",
+    "zipfile",
+    "zipimport",
+    "zlib",
+    "zoneinfo",
+];
+
+/// Where a dotted module name was found
+enum Module {
+    File(PathBuf),
+    /// A directory without `__init__.py`, which has no file of its own
+    Namespace,
+}
+
+#[derive(Clone, Default)]
+pub struct PyResolver {
+    source_roots: Vec<PathBuf>,
+    /// Package directories declared in `pyproject.toml`, by project root
+    project_roots: Arc<DashMap<PathBuf, Arc<Vec<PathBuf>>>>,
+}
+
+impl PyResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, config: &PythonConfig) -> Self {
+        self.source_roots = config.source_roots.clone().unwrap_or_default();
+        self
+    }
+
+    /// Resolves relative imports against the importing package and absolute
+    /// ones against the source roots. Standard library modules are external
+    /// unless shadowed by a module next to the script, as are modules that
+    /// can't be found locally.
+    pub async fn resolve(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<ResolvedImport>> {
+        let specifier = import.specifier.as_str();
+        let name = specifier.trim_start_matches('.');
+        let level = specifier.len() - name.len();
+        let parts: Vec<&str> = name.split('.').filter(|p| !p.is_empty()).collect();
+        let submodule = submodule_name(import);
+
+        let module = if level > 0 {
+            let mut base = from_file.parent().unwrap_or_else(|| Path::new("/"));
+            for _ in 1..level {
+                base = base.parent().unwrap_or(base);
+            }
+            find_module(base, &parts, submodule, fs).await
+        } else if parts.is_empty() {
+            return Ok(None);
+        } else if STDLIB_MODULES.contains(&parts[0]) {
+            // The script's own directory comes before the standard library on
+            // `sys.path`, so a local module of the same name shadows it
+            let dir = script_dir(from_file, fs).await;
+            match find_module(&dir, &parts, submodule, fs).await {
+                Some(Module::File(path)) => Some(Module::File(path)),
+                _ => {
+                    log::debug!("Skipping standard library module '{}'", specifier);
+                    return Ok(None);
+                }
+            }
+        } else {
+            let mut found = None;
+            for root in self.roots_for(from_file, fs).await {
+                match find_module(&root, &parts, submodule, fs).await {
+                    Some(Module::File(path)) => {
+                        found = Some(Module::File(path));
+                        break;
+                    }
+                    // Later roots may still hold a regular package of that name
+                    Some(Module::Namespace) => found = found.or(Some(Module::Namespace)),
+                    None => {}
+                }
+            }
+            found
+        };
+
+        match module {
+            Some(Module::File(path)) if !is_installed(&path) => Ok(Some(ResolvedImport {
+                path: path.absolutize()?.to_path_buf(),
+                is_local: true,
+                is_asset: false,
+            })),
+            Some(Module::Namespace) => {
+                log::debug!("'{}' is a namespace package without files", specifier);
+                Ok(None)
+            }
+            _ => {
+                log::debug!(
+                    "Treating '{}' from {} as external",
+                    specifier,
+                    from_file.display()
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Directories absolute imports are looked up in, in order: configured
+    /// source roots, `pyproject.toml` package directories, the project root
+    /// and its `src/`, then the directory above the outermost package
+    /// containing `from_file`
+    async fn roots_for(&self, from_file: &Path, fs: &dyn FileSystemProvider) -> Vec<PathBuf> {
+        let mut roots = self.source_roots.clone();
+        let dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+
+        if let Some(project_root) = find_project_root(dir, fs).await {
+            roots.extend(
+                self.project_package_dirs(&project_root, fs)
+                    .await
+                    .iter()
+                    .cloned(),
+            );
+            roots.push(project_root.join("src"));
+            roots.push(project_root);
+        }
+
+        roots.push(script_dir(from_file, fs).await);
+
+        let mut unique = Vec::new();
+        for root in roots {
+            if !unique.contains(&root) {
+                unique.push(root);
+            }
+        }
+        unique
+    }
+
+    async fn project_package_dirs(
+        &self,
+        project_root: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Arc<Vec<PathBuf>> {
+        if let Some(cached) = self.project_roots.get(project_root) {
+            return cached.clone();
+        }
+
+        let dirs = match fs.read_file(&project_root.join("pyproject.toml")).await {
+            Ok(content) => pyproject_package_dirs(&content)
+                .into_iter()
+                .map(|dir| project_root.join(dir))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        let dirs = Arc::new(dirs);
+        self.project_roots
+            .insert(project_root.to_path_buf(), dirs.clone());
+        dirs
+    }
+}
+
+/// The directory on `sys.path` when `from_file`, or the package it belongs
+/// to, is run: the file's own directory, or the one above its outermost
+/// package
+async fn script_dir(from_file: &Path, fs: &dyn FileSystemProvider) -> PathBuf {
+    let mut dir = from_file.parent().unwrap_or_else(|| Path::new("/"));
+    while fs.exists(&dir.join("__init__.py")).await {
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => break,
+        }
+    }
+    dir.to_path_buf()
+}
+
+/// The name a single-name `from x import name` statement imports, which may
+/// refer to a submodule rather than an attribute
+fn submodule_name(import: &ImportStatement) -> Option<&str> {
+    match import.bindings.as_slice() {
+        [ImportBinding::Named { imported, .. }] => Some(imported),
+        _ => None,
+    }
+}
+
+/// Looks up `parts` below `base`, preferring the submodule `submodule` of it
+async fn find_module(
+    base: &Path,
+    parts: &[&str],
+    submodule: Option<&str>,
+    fs: &dyn FileSystemProvider,
+) -> Option<Module> {
+    let dir = parts.iter().fold(base.to_path_buf(), |dir, p| dir.join(p));
+
+    if let Some(name) = submodule {
+        if let Some(Module::File(path)) = module_at(&dir.join(name), fs).await {
+            return Some(Module::File(path));
+        }
+    }
+    if parts.is_empty() {
+        // `from . import x` where `x` is defined in the package itself
+        let init = dir.join("__init__.py");
+        return Some(if fs.exists(&init).await {
+            Module::File(init)
+        } else {
+            Module::Namespace
+        });
+    }
+    module_at(&dir, fs).await
+}
+
+/// The module file for `path` as a module (`path.py`) or package
+/// (`path/__init__.py`), with `.pyi` stubs as a fallback
+async fn module_at(path: &Path, fs: &dyn FileSystemProvider) -> Option<Module> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let candidates = [
+        path.with_file_name(format!("{}.py", file_name)),
+        path.join("__init__.py"),
+        path.with_file_name(format!("{}.pyi", file_name)),
+        path.join("__init__.pyi"),
+    ];
+    for candidate in candidates {
+        if fs.exists(&candidate).await && !fs.is_directory(&candidate).await {
+            return Some(Module::File(candidate));
+        }
+    }
+    fs.is_directory(path).await.then_some(Module::Namespace)
+}
+
+async fn find_project_root(dir: &Path, fs: &dyn FileSystemProvider) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        for name in PROJECT_FILES {
+            if fs.exists(&ancestor.join(name)).await {
+                return Some(ancestor.to_path_buf());
+            }
+        }
+    }
+    None
+}
+
+fn is_installed(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(name) => INSTALL_DIRS.contains(&name.to_str().unwrap_or("")),
+        _ => false,
+    })
+}
+
+/// Directories containing top-level packages according to the setuptools,
+/// Poetry, Hatch and PDM settings in `pyproject.toml`
+fn pyproject_package_dirs(content: &str) -> Vec<String> {
+    let Ok(pyproject) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let tool = |path: &[&str]| {
+        path.iter()
+            .try_fold(pyproject.get("tool")?, |value, key| value.get(key))
+    };
+    let parent_of = |package: &str| match package.trim_end_matches('/').rsplit_once('/') {
+        Some((parent, _)) => parent.to_string(),
+        None => ".".to_string(),
+    };
+
+    let mut dirs = Vec::new();
+
+    // [tool.setuptools.package-dir] "" = "src"
+    if let Some(dir) = tool(&["setuptools", "package-dir", ""]).and_then(|v| v.as_str()) {
+        dirs.push(dir.to_string());
+    }
+    // [tool.setuptools.packages.find] where = ["src"]
+    if let Some(wheres) =
+        tool(&["setuptools", "packages", "find", "where"]).and_then(|v| v.as_array())
+    {
+        dirs.extend(wheres.iter().filter_map(|w| w.as_str()).map(str::to_string));
+    }
+    // [tool.poetry] packages = [{ include = "pkg", from = "src" }]
+    if let Some(packages) = tool(&["poetry", "packages"]).and_then(|v| v.as_array()) {
+        dirs.extend(packages.iter().map(|p| {
+            p.get("from")
+                .and_then(|f| f.as_str())
+                .unwrap_or(".")
+                .to_string()
+        }));
+    }
+    // [tool.hatch.build.targets.wheel] packages = ["src/pkg"]
+    if let Some(packages) =
+        tool(&["hatch", "build", "targets", "wheel", "packages"]).and_then(|v| v.as_array())
+    {
+        dirs.extend(packages.iter().filter_map(|p| p.as_str()).map(parent_of));
+    }
+    // [tool.pdm.build] package-dir = "src"
+    if let Some(dir) = tool(&["pdm", "build", "package-dir"]).and_then(|v| v.as_str()) {
+        dirs.push(dir.to_string());
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::PYTHON_IMPORT;
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+    use crate::core::language::ImportKind;
+
+    #[tokio::test]
+    async fn test_script_directory_shadows_standard_library() {
+        let fs = MemoryFileSystem::new([
+            ("/proj/tool/run.py", ""),
+            ("/proj/tool/logging.py", ""),
+            ("/proj/app/__init__.py", ""),
+            ("/proj/app/views.py", ""),
+            ("/proj/json.py", ""),
+        ]);
+        let resolve = |specifier: &'static str, from: &'static str| {
+            let fs = &fs;
+            async move {
+                let import = ImportStatement {
+                    specifier: specifier.to_string(),
+                    kind: ImportKind::Custom(PYTHON_IMPORT.to_string()),
+                    line: 1,
+                    column: 0,
+                    raw: String::new(),
+                    bindings: Vec::new(),
+                };
+                PyResolver::new()
+                    .resolve(&import, Path::new(from), fs)
+                    .await
+                    .unwrap()
+                    .map(|r| r.path.display().to_string())
+            }
+        };
+
+        assert_eq!(
+            resolve("logging", "/proj/tool/run.py").await.as_deref(),
+            Some("/proj/tool/logging.py")
+        );
+        assert_eq!(resolve("os", "/proj/tool/run.py").await, None);
+        // Inside a package, `sys.path` starts above the outermost package
+        assert_eq!(
+            resolve("json", "/proj/app/views.py").await.as_deref(),
+            Some("/proj/json.py")
+        );
+        assert_eq!(resolve("logging", "/proj/app/views.py").await, None);
+    }
+
+    #[test]
+    fn test_pyproject_package_dirs() {
+        let content = r#"
+[tool.setuptools.packages.find]
+where = ["src"]
+
+[tool.poetry]
+packages = [{ include = "api", from = "services" }, { include = "tools" }]
+
+[tool.hatch.build.targets.wheel]
+packages = ["lib/core"]
+"#;
+        assert_eq!(
+            pyproject_package_dirs(content),
+            vec!["src", "services", ".", "lib"]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+mod parser;
+mod resolver;
+
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
+use anyhow::Result;
+use async_trait::async_trait;
+use parser::RustParser;
+use resolver::RustResolver;
+use std::path::Path;
+
+#[derive(Clone, Default)]
+pub struct RustAdapter {
+    parser: RustParser,
+    resolver: RustResolver,
+}
+
+impl RustAdapter {
+    pub fn new() -> Self {
+        Self {
+            parser: RustParser::new(),
+            resolver: RustResolver::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl LanguageAdapter for RustAdapter {
+    fn name(&self) -> &str {
+        "Rust"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["rs"]
+    }
+
+    async fn parse_imports(
+        &self,
+        _file_path: &Path,
+        content: &str,
+        _context: &AnalysisContext,
+    ) -> Result<Vec<ImportStatement>> {
+        Ok(self.parser.parse(content))
+    }
+
+    async fn resolve_import(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Option<ResolvedImport>> {
+        self.resolver.resolve(import, from_file, &*context.fs).await
+    }
+}
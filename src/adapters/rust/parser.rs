@@ -0,0 +1,518 @@
+use crate::core::language::{ImportKind, ImportStatement};
+
+/// Kind of `mod name;` declarations
+pub const MOD_DECLARATION: &str = "mod";
+/// Kind of `use` declarations, except `pub use`, which are re-exports
+pub const USE_DECLARATION: &str = "use";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    /// Contents of a string literal
+    Str(String),
+}
+
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct RustParser;
+
+impl RustParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Finds `mod name;` declarations, honouring `#[path = "..."]`, and the
+    /// paths of every `use` declaration. Paths inside inline modules are
+    /// rewritten relative to the file's own module, so `super::x` inside
+    /// `mod tests { ... }` becomes `self::x`.
+    ///
+    /// A `mod` with a `#[path]` attribute gets the attribute's value as its
+    /// specifier, which always ends in `.rs`.
+    pub fn parse(&self, content: &str) -> Vec<ImportStatement> {
+        let tokens = tokenize(content);
+        let lines: Vec<&str> = content.lines().collect();
+        let raw = |line: usize| lines.get(line - 1).map_or("", |l| l.trim()).to_string();
+
+        let mut imports = Vec::new();
+        // Inline modules enclosing the current position; `None` for other blocks
+        let mut scopes: Vec<Option<String>> = Vec::new();
+        let mut path_attr: Option<String> = None;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            match &tokens[i].token {
+                Token::Punct('#') => {
+                    let (end, path) = attribute(&tokens, i);
+                    if path.is_some() {
+                        path_attr = path;
+                    }
+                    i = end;
+                    continue;
+                }
+                Token::Punct('{') => scopes.push(None),
+                Token::Punct('}') => {
+                    scopes.pop();
+                }
+                Token::Ident(word) if word == "mod" => {
+                    if let Some(Token::Ident(name)) = tokens.get(i + 1).map(|t| &t.token) {
+                        match tokens.get(i + 2).map(|t| &t.token) {
+                            Some(Token::Punct(';')) => {
+                                let inline: Vec<&str> = inline_modules(&scopes);
+                                let specifier = match path_attr.take() {
+                                    Some(path) => path,
+                                    None => inline
+                                        .iter()
+                                        .copied()
+                                        .chain([name.as_str()])
+                                        .collect::<Vec<_>>()
+                                        .join("::"),
+                                };
+                                imports.push(ImportStatement {
+                                    specifier,
+                                    kind: ImportKind::Custom(MOD_DECLARATION.to_string()),
+                                    line: tokens[i].line,
+                                    column: tokens[i].column,
+                                    raw: raw(tokens[i].line),
+                                    bindings: Vec::new(),
+                                });
+                                i += 3;
+                                continue;
+                            }
+                            Some(Token::Punct('{')) => {
+                                scopes.push(Some(name.clone()));
+                                path_attr = None;
+                                i += 3;
+                                continue;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Token::Ident(word) if word == "use" => {
+                    let end = tokens[i..]
+                        .iter()
+                        .position(|t| t.token == Token::Punct(';'))
+                        .map_or(tokens.len(), |p| i + p);
+                    let kind = if is_public(&tokens, i) {
+                        ImportKind::ReExport
+                    } else {
+                        ImportKind::Custom(USE_DECLARATION.to_string())
+                    };
+                    let inline = inline_modules(&scopes);
+
+                    let mut paths = Vec::new();
+                    let tree: Vec<&Token> = tokens[i + 1..end].iter().map(|t| &t.token).collect();
+                    use_tree(&tree, &mut Vec::new(), &mut paths);
+                    for path in paths {
+                        let Some(specifier) = file_relative(path, &inline) else {
+                            continue;
+                        };
+                        imports.push(ImportStatement {
+                            specifier,
+                            kind: kind.clone(),
+                            line: tokens[i].line,
+                            column: tokens[i].column,
+                            raw: raw(tokens[i].line),
+                            bindings: Vec::new(),
+                        });
+                    }
+                    path_attr = None;
+                    i = end + 1;
+                    continue;
+                }
+                Token::Punct(';') => path_attr = None,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        imports
+    }
+}
+
+fn inline_modules(scopes: &[Option<String>]) -> Vec<&str> {
+    scopes.iter().flatten().map(String::as_str).collect()
+}
+
+/// Whether the `use` at `index` is preceded by `pub` or `pub(...)`
+fn is_public(tokens: &[Spanned], index: usize) -> bool {
+    let mut i = index;
+    if i > 0 && tokens[i - 1].token == Token::Punct(')') {
+        while i > 0 && tokens[i - 1].token != Token::Punct('(') {
+            i -= 1;
+        }
+        i = i.saturating_sub(1);
+    }
+    i > 0 && tokens[i - 1].token == Token::Ident("pub".to_string())
+}
+
+/// Skips the attribute starting at `start`, returning the index after it and
+/// the value of a `#[path = "..."]` attribute
+fn attribute(tokens: &[Spanned], start: usize) -> (usize, Option<String>) {
+    let mut i = start + 1;
+    if tokens.get(i).map(|t| &t.token) == Some(&Token::Punct('!')) {
+        i += 1;
+    }
+    if tokens.get(i).map(|t| &t.token) != Some(&Token::Punct('[')) {
+        return (start + 1, None);
+    }
+
+    let body_start = i + 1;
+    let mut depth = 0;
+    while i < tokens.len() {
+        match tokens[i].token {
+            Token::Punct('[') => depth += 1,
+            Token::Punct(']') => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let body: Vec<&Token> = tokens[body_start..i.min(tokens.len())]
+        .iter()
+        .map(|t| &t.token)
+        .collect();
+    let path = match body.as_slice() {
+        [Token::Ident(name), Token::Punct('='), Token::Str(value)] if name == "path" => {
+            Some(value.clone())
+        }
+        _ => None,
+    };
+    (i + 1, path)
+}
+
+/// Expands a use tree such as `crate::a::{b, c::*, self}` into the paths it
+/// names, dropping `*` and renames
+fn use_tree(tokens: &[&Token], prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    let mut i = 0;
+    let depth = prefix.len();
+    // A leading `::` names an external crate in the 2018 edition
+    while tokens.get(i) == Some(&&Token::Punct(':')) {
+        i += 1;
+    }
+
+    let mut complete = false;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Ident(segment) if segment == "as" => {
+                complete = true;
+                break;
+            }
+            Token::Ident(segment) if segment == "self" && depth > 0 && prefix.len() == depth => {
+                // `{self}` names the prefix itself
+                i += 1;
+            }
+            Token::Ident(segment) => {
+                prefix.push(segment.clone());
+                i += 1;
+            }
+            Token::Punct(':') => i += 1,
+            Token::Punct('*') => {
+                complete = true;
+                break;
+            }
+            Token::Punct('{') => {
+                let mut level = 0;
+                let mut item_start = i + 1;
+                for (j, token) in tokens.iter().enumerate().skip(i) {
+                    match token {
+                        Token::Punct('{') => level += 1,
+                        Token::Punct('}') => {
+                            level -= 1;
+                            if level == 0 {
+                                use_tree(&tokens[item_start..j], prefix, paths);
+                                break;
+                            }
+                        }
+                        Token::Punct(',') if level == 1 => {
+                            use_tree(&tokens[item_start..j], prefix, paths);
+                            item_start = j + 1;
+                        }
+                        _ => {}
+                    }
+                }
+                prefix.truncate(depth);
+                return;
+            }
+            // `$crate` in macros and anything unexpected
+            _ => {
+                prefix.truncate(depth);
+                return;
+            }
+        }
+    }
+
+    if (complete || i == tokens.len()) && !prefix.is_empty() {
+        paths.push(prefix.clone());
+    }
+    prefix.truncate(depth);
+}
+
+/// Rewrites a path used inside the inline modules `inline` so it is relative
+/// to the file's own module. Paths leaving the file's module through `super`
+/// keep their remaining `super`s.
+fn file_relative(path: Vec<String>, inline: &[&str]) -> Option<String> {
+    if inline.is_empty() || path.is_empty() {
+        return (!path.is_empty()).then(|| path.join("::"));
+    }
+
+    let mut module: Vec<String> = inline.iter().map(|s| s.to_string()).collect();
+    let mut rest = path.as_slice();
+    match rest[0].as_str() {
+        "self" => rest = &rest[1..],
+        "super" => {
+            while rest.first().map(String::as_str) == Some("super") && !module.is_empty() {
+                module.pop();
+                rest = &rest[1..];
+            }
+        }
+        _ => return Some(path.join("::")),
+    }
+
+    let mut segments = vec!["self".to_string()];
+    if rest.first().map(String::as_str) == Some("super") {
+        segments.clear();
+    }
+    segments.extend(module);
+    segments.extend(rest.iter().cloned());
+    Some(segments.join("::"))
+}
+
+/// Splits Rust source into identifiers, punctuation and string literals,
+/// dropping comments, whitespace, numbers, lifetimes and char literals
+fn tokenize(content: &str) -> Vec<Spanned> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (1, 0);
+    let mut i = 0;
+
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize, n: usize| {
+        for c in &chars[*i..(*i + n).min(chars.len())] {
+            if *c == '\n' {
+                *line += 1;
+                *column = 0;
+            } else {
+                *column += 1;
+            }
+        }
+        *i += n;
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            advance(&mut i, &mut line, &mut column, 1);
+        } else if c == '/' && next == Some('/') {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            let len = end - i;
+            advance(&mut i, &mut line, &mut column, len);
+        } else if c == '/' && next == Some('*') {
+            // Block comments nest
+            let mut depth = 0;
+            let mut j = i;
+            while j < chars.len() {
+                if chars[j] == '/' && chars.get(j + 1) == Some(&'*') {
+                    depth += 1;
+                    j += 2;
+                } else if chars[j] == '*' && chars.get(j + 1) == Some(&'/') {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            let len = j - i;
+            advance(&mut i, &mut line, &mut column, len);
+        } else if let Some((len, value)) = string_literal(&chars[i..]) {
+            let (start_line, start_column) = (line, column);
+            advance(&mut i, &mut line, &mut column, len);
+            tokens.push(Spanned {
+                token: Token::Str(value),
+                line: start_line,
+                column: start_column,
+            });
+        } else if c == '\'' {
+            // Char literals; lifetimes and labels are skipped with the quote
+            let len = if next == Some('\\') {
+                chars[i + 2..]
+                    .iter()
+                    .position(|c| *c == '\'')
+                    .map_or(chars.len() - i, |p| p + 3)
+            } else if chars.get(i + 2) == Some(&'\'') {
+                3
+            } else {
+                1
+            };
+            advance(&mut i, &mut line, &mut column, len);
+        } else if c.is_alphanumeric() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                .unwrap_or(chars.len() - i);
+            let word: String = chars[i..i + len].iter().collect();
+            let (start_line, start_column) = (line, column);
+            advance(&mut i, &mut line, &mut column, len);
+            if !c.is_ascii_digit() {
+                let word = word.strip_prefix("r#").unwrap_or(&word).to_string();
+                tokens.push(Spanned {
+                    token: Token::Ident(word),
+                    line: start_line,
+                    column: start_column,
+                });
+            }
+        } else {
+            tokens.push(Spanned {
+                token: Token::Punct(c),
+                line,
+                column,
+            });
+            advance(&mut i, &mut line, &mut column, 1);
+        }
+    }
+
+    tokens
+}
+
+/// A string literal at the start of `chars`, including byte, C and raw
+/// strings, as its length in chars and its unescaped-enough contents
+fn string_literal(chars: &[char]) -> Option<(usize, String)> {
+    let mut i = 0;
+    if matches!(chars.first(), Some('b' | 'c')) {
+        i += 1;
+    }
+    let raw = chars.get(i) == Some(&'r');
+    if raw {
+        i += 1;
+    }
+    let hashes = chars[i..].iter().take_while(|c| **c == '#').count();
+    i += hashes;
+    if chars.get(i) != Some(&'"') || (hashes > 0 && !raw) {
+        return None;
+    }
+    i += 1;
+
+    let mut value = String::new();
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && !raw {
+            if let Some(escaped) = chars.get(i + 1) {
+                value.push(*escaped);
+            }
+            i += 2;
+            continue;
+        }
+        if c == '"'
+            && chars[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|c| **c == '#')
+                .count()
+                == hashes
+        {
+            return Some((i + 1 + hashes, value));
+        }
+        value.push(c);
+        i += 1;
+    }
+    Some((chars.len(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specifiers(content: &str) -> Vec<String> {
+        RustParser::new()
+            .parse(content)
+            .into_iter()
+            .map(|i| i.specifier)
+            .collect()
+    }
+
+    #[test]
+    fn test_use_trees_expand_to_paths() {
+        assert_eq!(
+            specifiers("use crate::config::{Config, loader::{self, Loader as L}, prelude::*};"),
+            vec![
+                "crate::config::Config",
+                "crate::config::loader",
+                "crate::config::loader::Loader",
+                "crate::config::prelude",
+            ]
+        );
+        assert_eq!(
+            specifiers("use ::serde::Serialize;"),
+            vec!["serde::Serialize"]
+        );
+        assert_eq!(specifiers("use super::{self as parent};"), vec!["super"]);
+    }
+
+    #[test]
+    fn test_public_uses_are_reexports() {
+        let imports = RustParser::new().parse("pub use a::B;\npub(crate) use c::D;\nuse e::F;");
+        let kinds: Vec<bool> = imports
+            .iter()
+            .map(|i| matches!(i.kind, ImportKind::ReExport))
+            .collect();
+        assert_eq!(kinds, vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_path_attribute_applies_to_next_mod_only() {
+        let content = "#[cfg(unix)]\n#[path = \"sys/unix.rs\"]\nmod sys;\n#[path = \"ignored.rs\"]\nconst X: u8 = 1;\nmod plain;";
+        let imports = RustParser::new().parse(content);
+
+        assert_eq!(
+            imports
+                .iter()
+                .map(|i| i.specifier.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sys/unix.rs", "plain"]
+        );
+        assert_eq!((imports[0].line, imports[0].column), (3, 0));
+    }
+
+    #[test]
+    fn test_inline_module_paths_are_file_relative() {
+        let content = "mod a {\n    mod b {\n        use super::c;\n        use super::super::Root;\n        mod d;\n    }\n    fn f() { use self::x::Y; }\n}";
+        assert_eq!(
+            specifiers(content),
+            vec!["self::a::c", "self::Root", "a::b::d", "self::a::x::Y"]
+        );
+        // `super` past the file's own module is kept
+        assert_eq!(
+            specifiers("mod tests {\n    use super::super::Root;\n}"),
+            vec!["super::Root"]
+        );
+    }
+
+    #[test]
+    fn test_literals_and_comments_are_skipped() {
+        let content = r##"//! mod hidden;
+/* outer /* nested */ use still::Comment; */
+const TEMPLATE: &str = r#"use crate::fake; mod fake;"#;
+fn label<'a>(s: &'a str) -> char { '{' }
+mod real;
+"##;
+        assert_eq!(specifiers(content), vec!["real"]);
+    }
+}
@@ -0,0 +1,524 @@
+use super::parser::MOD_DECLARATION;
+use crate::core::fs::FileSystemProvider;
+use crate::core::language::{ImportKind, ImportStatement, ResolvedImport};
+use anyhow::Result;
+use dashmap::DashMap;
+use path_absolutize::Absolutize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// What packlet needs to know about a crate from its `Cargo.toml`
+#[derive(Debug, Default)]
+struct CrateManifest {
+    /// Library root, if the crate has one
+    lib: Option<PathBuf>,
+    /// Name binaries and tests use to refer to the library
+    lib_name: Option<String>,
+    /// Binary roots declared with `[[bin]] path`
+    bins: Vec<PathBuf>,
+    /// Crate directories of path dependencies, by the name used in code
+    path_deps: HashMap<String, PathBuf>,
+}
+
+#[derive(Clone, Default)]
+pub struct RustResolver {
+    manifests: Arc<DashMap<PathBuf, Arc<CrateManifest>>>,
+}
+
+impl RustResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `mod` declarations to their files and `use` paths to the
+    /// file of the deepest module they name. Paths into other crates are
+    /// external unless the crate is a path dependency.
+    pub async fn resolve(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        fs: &dyn FileSystemProvider,
+    ) -> Result<Option<ResolvedImport>> {
+        let Some(crate_dir) = find_crate_dir(from_file, fs).await else {
+            log::debug!("{} is not part of a crate", from_file.display());
+            return Ok(None);
+        };
+        let manifest = self.manifest(&crate_dir, fs).await;
+
+        let resolved = if matches!(&import.kind, ImportKind::Custom(kind) if kind == MOD_DECLARATION)
+        {
+            self.resolve_mod(&import.specifier, from_file, &crate_dir, &manifest, fs)
+                .await
+        } else {
+            self.resolve_use(&import.specifier, from_file, &crate_dir, &manifest, fs)
+                .await
+        };
+
+        match resolved {
+            Some(path) if path != from_file => Ok(Some(ResolvedImport {
+                path: path.absolutize()?.to_path_buf(),
+                is_local: true,
+                is_asset: false,
+            })),
+            Some(_) => Ok(None),
+            None => {
+                log::debug!(
+                    "Treating '{}' from {} as external",
+                    import.specifier,
+                    from_file.display()
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    async fn resolve_mod(
+        &self,
+        specifier: &str,
+        from_file: &Path,
+        crate_dir: &Path,
+        manifest: &CrateManifest,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<PathBuf> {
+        let dir = from_file.parent()?;
+        if specifier.ends_with(".rs") {
+            // `#[path]` is relative to the declaring file's directory
+            let path = dir.join(specifier);
+            return is_file(&path, fs).await.then_some(path);
+        }
+
+        let module_dir = module_dir(from_file, crate_dir, manifest);
+        let segments: Vec<&str> = specifier.split("::").collect();
+        let (name, inline) = segments.split_last()?;
+        let parent = inline.iter().fold(module_dir, |dir, s| dir.join(s));
+        module_file(&parent, name, fs).await
+    }
+
+    async fn resolve_use(
+        &self,
+        specifier: &str,
+        from_file: &Path,
+        crate_dir: &Path,
+        manifest: &CrateManifest,
+        fs: &dyn FileSystemProvider,
+    ) -> Option<PathBuf> {
+        let segments: Vec<&str> = specifier.split("::").collect();
+        let (first, rest) = segments.split_first()?;
+
+        match *first {
+            "crate" => {
+                let root = crate_root(from_file, crate_dir, manifest);
+                let dir = root.parent()?.to_path_buf();
+                Some(deepest_module(&dir, rest, fs).await.unwrap_or(root))
+            }
+            "self" => {
+                let dir = module_dir(from_file, crate_dir, manifest);
+                deepest_module(&dir, rest, fs).await
+            }
+            "super" => {
+                let root = crate_root(from_file, crate_dir, manifest);
+                let root_dir = root.parent()?.to_path_buf();
+                let mut dir = module_dir(from_file, crate_dir, manifest);
+                let mut rest = rest;
+                let mut levels = 1;
+                while rest.first() == Some(&"super") {
+                    levels += 1;
+                    rest = &rest[1..];
+                }
+                for _ in 0..levels {
+                    if dir == root_dir {
+                        return None;
+                    }
+                    dir = dir.parent()?.to_path_buf();
+                }
+
+                if let Some(found) = deepest_module(&dir, rest, fs).await {
+                    return Some(found);
+                }
+                if dir == root_dir {
+                    return Some(root);
+                }
+                let name = dir.file_name()?.to_str()?;
+                module_file(dir.parent()?, name, fs).await
+            }
+            "std" | "core" | "alloc" => None,
+            name => {
+                // A child module of the current module, in 2018 edition paths
+                let dir = module_dir(from_file, crate_dir, manifest);
+                if module_file(&dir, name, fs).await.is_some() {
+                    return deepest_module(&dir, &segments, fs).await;
+                }
+
+                // Binaries use their own crate's library by name
+                let lib = if manifest.lib_name.as_deref() == Some(name) {
+                    manifest.lib.clone()?
+                } else {
+                    let dep_dir = manifest.path_deps.get(name)?;
+                    self.manifest(dep_dir, fs).await.lib.clone()?
+                };
+                let dir = lib.parent()?.to_path_buf();
+                Some(deepest_module(&dir, rest, fs).await.unwrap_or(lib))
+            }
+        }
+    }
+
+    async fn manifest(&self, crate_dir: &Path, fs: &dyn FileSystemProvider) -> Arc<CrateManifest> {
+        if let Some(cached) = self.manifests.get(crate_dir) {
+            return cached.clone();
+        }
+
+        let manifest = match fs.read_file(&crate_dir.join("Cargo.toml")).await {
+            Ok(content) => parse_manifest(crate_dir, &content, fs).await,
+            Err(_) => CrateManifest::default(),
+        };
+        let manifest = Arc::new(manifest);
+        self.manifests
+            .insert(crate_dir.to_path_buf(), manifest.clone());
+        manifest
+    }
+}
+
+async fn is_file(path: &Path, fs: &dyn FileSystemProvider) -> bool {
+    fs.exists(path).await && !fs.is_directory(path).await
+}
+
+/// The nearest directory above `file` with a `Cargo.toml`
+async fn find_crate_dir(file: &Path, fs: &dyn FileSystemProvider) -> Option<PathBuf> {
+    for dir in file.ancestors().skip(1) {
+        if fs.exists(&dir.join("Cargo.toml")).await {
+            return Some(dir.to_path_buf());
+        }
+    }
+    None
+}
+
+/// Whether `file` is the root of a crate target rather than a module
+fn is_crate_root(file: &Path, crate_dir: &Path, manifest: &CrateManifest) -> bool {
+    let src = crate_dir.join("src");
+    manifest.lib.as_deref() == Some(file)
+        || manifest.bins.iter().any(|bin| bin == file)
+        || file == src.join("main.rs")
+        || file.parent() == Some(&src.join("bin"))
+        || (file.file_name().is_some_and(|n| n == "main.rs")
+            && file.parent().and_then(|p| p.parent()) == Some(&src.join("bin")))
+}
+
+/// The crate root `crate::` refers to in `file`: the binary root for files
+/// under `src/bin/<name>/` and `src/main.rs` itself, otherwise the library
+/// root, falling back to `src/main.rs`
+fn crate_root(file: &Path, crate_dir: &Path, manifest: &CrateManifest) -> PathBuf {
+    if is_crate_root(file, crate_dir, manifest) {
+        return file.to_path_buf();
+    }
+    let bin_dir = crate_dir.join("src").join("bin");
+    if let Ok(relative) = file.strip_prefix(&bin_dir) {
+        if let Some(name) = relative.components().next() {
+            return bin_dir.join(name).join("main.rs");
+        }
+    }
+    manifest
+        .lib
+        .clone()
+        .unwrap_or_else(|| crate_dir.join("src").join("main.rs"))
+}
+
+/// Directory holding the files of `file`'s child modules
+fn module_dir(file: &Path, crate_dir: &Path, manifest: &CrateManifest) -> PathBuf {
+    let dir = file.parent().unwrap_or_else(|| Path::new("/"));
+    let is_mod_rs = file.file_name().is_some_and(|n| n == "mod.rs");
+    if is_mod_rs || is_crate_root(file, crate_dir, manifest) {
+        dir.to_path_buf()
+    } else {
+        dir.join(file.file_stem().unwrap_or_default())
+    }
+}
+
+/// `dir/name.rs` or `dir/name/mod.rs`
+async fn module_file(dir: &Path, name: &str, fs: &dyn FileSystemProvider) -> Option<PathBuf> {
+    for candidate in [
+        dir.join(format!("{}.rs", name)),
+        dir.join(name).join("mod.rs"),
+    ] {
+        if is_file(&candidate, fs).await {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// The file of the deepest module `segments` names below `dir`; the rest of
+/// the path names items inside it
+async fn deepest_module(
+    dir: &Path,
+    segments: &[&str],
+    fs: &dyn FileSystemProvider,
+) -> Option<PathBuf> {
+    let mut dir = dir.to_path_buf();
+    let mut found = None;
+    for segment in segments {
+        match module_file(&dir, segment, fs).await {
+            Some(file) => {
+                found = Some(file);
+                dir = dir.join(segment);
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+async fn parse_manifest(
+    crate_dir: &Path,
+    content: &str,
+    fs: &dyn FileSystemProvider,
+) -> CrateManifest {
+    let Ok(cargo) = content.parse::<toml::Table>() else {
+        return CrateManifest::default();
+    };
+
+    let lib_path = cargo
+        .get("lib")
+        .and_then(|lib| lib.get("path"))
+        .and_then(|p| p.as_str())
+        .map(|p| crate_dir.join(p));
+    let default_lib = crate_dir.join("src").join("lib.rs");
+    let lib = match lib_path {
+        Some(path) => Some(path),
+        None => is_file(&default_lib, fs).await.then_some(default_lib),
+    };
+
+    let lib_name = cargo
+        .get("lib")
+        .and_then(|lib| lib.get("name"))
+        .or_else(|| cargo.get("package").and_then(|package| package.get("name")))
+        .and_then(|name| name.as_str())
+        .map(|name| name.replace('-', "_"));
+
+    let bins = cargo
+        .get("bin")
+        .and_then(|bins| bins.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|bin| bin.get("path").and_then(|p| p.as_str()))
+        .map(|p| crate_dir.join(p))
+        .collect();
+
+    let mut tables: Vec<&toml::Table> = ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|key| cargo.get(*key).and_then(|d| d.as_table()))
+        .collect();
+    // [target.'cfg(...)'.dependencies]
+    if let Some(targets) = cargo.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values() {
+            tables.extend(
+                ["dependencies", "dev-dependencies", "build-dependencies"]
+                    .iter()
+                    .filter_map(|key| target.get(*key).and_then(|d| d.as_table())),
+            );
+        }
+    }
+
+    let mut workspace: Option<(PathBuf, toml::Table)> = None;
+    let mut path_deps = HashMap::new();
+    for table in tables {
+        for (name, spec) in table {
+            let dir = if let Some(path) = spec.get("path").and_then(|p| p.as_str()) {
+                Some(crate_dir.join(path))
+            } else if spec.get("workspace").and_then(|w| w.as_bool()) == Some(true) {
+                if workspace.is_none() {
+                    workspace = find_workspace(crate_dir, fs).await;
+                }
+                workspace.as_ref().and_then(|(root, cargo)| {
+                    let path = cargo
+                        .get("workspace")?
+                        .get("dependencies")?
+                        .get(name)?
+                        .get("path")?
+                        .as_str()?;
+                    Some(root.join(path))
+                })
+            } else {
+                None
+            };
+            if let Some(dir) = dir {
+                path_deps.insert(name.replace('-', "_"), dir);
+            }
+        }
+    }
+
+    CrateManifest {
+        lib,
+        lib_name,
+        bins,
+        path_deps,
+    }
+}
+
+/// The nearest `Cargo.toml` at or above `crate_dir` with a `[workspace]`
+async fn find_workspace(
+    crate_dir: &Path,
+    fs: &dyn FileSystemProvider,
+) -> Option<(PathBuf, toml::Table)> {
+    for dir in crate_dir.ancestors() {
+        let Ok(content) = fs.read_file(&dir.join("Cargo.toml")).await else {
+            continue;
+        };
+        if let Ok(cargo) = content.parse::<toml::Table>() {
+            if cargo.contains_key("workspace") {
+                return Some((dir.to_path_buf(), cargo));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::USE_DECLARATION;
+    use super::*;
+    use crate::core::fs::MemoryFileSystem;
+
+    fn workspace() -> MemoryFileSystem {
+        MemoryFileSystem::new([
+            (
+                "/ws/Cargo.toml",
+                "[workspace]\nmembers = [\"app\", \"shared\"]\n\n[workspace.dependencies]\nshared = { path = \"shared\" }\n",
+            ),
+            ("/ws/shared/Cargo.toml", "[package]\nname = \"shared\"\n"),
+            ("/ws/shared/src/lib.rs", ""),
+            ("/ws/shared/src/codec.rs", ""),
+            (
+                "/ws/app/Cargo.toml",
+                "[package]\nname = \"my-app\"\n\n[dependencies]\nshared = { workspace = true }\nserde = \"1\"\n",
+            ),
+            ("/ws/app/src/lib.rs", ""),
+            ("/ws/app/src/main.rs", ""),
+            ("/ws/app/src/config.rs", ""),
+            ("/ws/app/src/net/mod.rs", ""),
+            ("/ws/app/src/net/http.rs", ""),
+            ("/ws/app/src/net/http/client.rs", ""),
+            ("/ws/app/src/bin/tool/main.rs", ""),
+            ("/ws/app/src/bin/tool/cli.rs", ""),
+        ])
+    }
+
+    async fn resolve(
+        fs: &MemoryFileSystem,
+        kind: &str,
+        specifier: &str,
+        from: &str,
+    ) -> Option<String> {
+        let import = ImportStatement {
+            specifier: specifier.to_string(),
+            kind: ImportKind::Custom(kind.to_string()),
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        };
+        RustResolver::new()
+            .resolve(&import, Path::new(from), fs)
+            .await
+            .unwrap()
+            .map(|resolved| resolved.path.display().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_use_paths() {
+        let fs = workspace();
+        let cases = [
+            // super:: chains climb one module per segment
+            (
+                "super::Request",
+                "/ws/app/src/net/http/client.rs",
+                Some("/ws/app/src/net/http.rs"),
+            ),
+            (
+                "super::super::super::config::Settings",
+                "/ws/app/src/net/http/client.rs",
+                Some("/ws/app/src/config.rs"),
+            ),
+            (
+                "super::super::Server",
+                "/ws/app/src/net/http/client.rs",
+                Some("/ws/app/src/net/mod.rs"),
+            ),
+            // 2018 edition paths to child modules
+            (
+                "client::Client",
+                "/ws/app/src/net/http.rs",
+                Some("/ws/app/src/net/http/client.rs"),
+            ),
+            (
+                "net::http::client::Client",
+                "/ws/app/src/lib.rs",
+                Some("/ws/app/src/net/http/client.rs"),
+            ),
+            // Binaries use the library by its name, with `-` turned into `_`
+            (
+                "my_app::config::Settings",
+                "/ws/app/src/main.rs",
+                Some("/ws/app/src/config.rs"),
+            ),
+            // `workspace = true` path dependencies
+            (
+                "shared::codec::encode",
+                "/ws/app/src/config.rs",
+                Some("/ws/shared/src/codec.rs"),
+            ),
+            (
+                "shared::Error",
+                "/ws/app/src/config.rs",
+                Some("/ws/shared/src/lib.rs"),
+            ),
+            ("serde::Serialize", "/ws/app/src/config.rs", None),
+            ("std::fmt", "/ws/app/src/config.rs", None),
+        ];
+
+        for (specifier, from, expected) in cases {
+            assert_eq!(
+                resolve(&fs, USE_DECLARATION, specifier, from)
+                    .await
+                    .as_deref(),
+                expected,
+                "use {specifier} from {from}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_binary_directories_are_crate_roots() {
+        let fs = workspace();
+
+        assert_eq!(
+            resolve(&fs, MOD_DECLARATION, "cli", "/ws/app/src/bin/tool/main.rs")
+                .await
+                .as_deref(),
+            Some("/ws/app/src/bin/tool/cli.rs")
+        );
+        assert_eq!(
+            resolve(
+                &fs,
+                USE_DECLARATION,
+                "crate::Args",
+                "/ws/app/src/bin/tool/cli.rs"
+            )
+            .await
+            .as_deref(),
+            Some("/ws/app/src/bin/tool/main.rs")
+        );
+        // Library modules still see the library as `crate`
+        assert_eq!(
+            resolve(
+                &fs,
+                USE_DECLARATION,
+                "crate::Error",
+                "/ws/app/src/config.rs"
+            )
+            .await
+            .as_deref(),
+            Some("/ws/app/src/lib.rs")
+        );
+    }
+}
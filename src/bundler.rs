@@ -0,0 +1,724 @@
+use crate::adapters::javascript::{
+    signatures, slice_module, DeclarationCollector, SLICEABLE_EXTENSIONS,
+};
+use crate::config::PackletConfig;
+use crate::core::cache::{CachedAdapter, ParseCache};
+use crate::core::fs::{find_git_root, CachedFileSystem, FileSystemProvider, LocalFileSystem};
+use crate::core::language::{
+    get_adapter_for_extension, get_adapters, AnalysisContext, LanguageAdapter,
+};
+use crate::core::traverser::{matches_extensions, DependencyGraph, DependencyTraverser};
+use crate::core::usage::{used_exports, Usage};
+use crate::output::budget::{apply_token_budget, distances_from_entry, BudgetReport};
+use crate::output::{
+    ApproxTokenizer, BundleNotes, ExternalApi, MarkdownFormatter, OutputFormatter, Tokenizer,
+    XmlFormatter,
+};
+use crate::output::{Detail, OutputFormat};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Progress events reported while building a graph or bundle
+#[derive(Debug)]
+pub enum BundleProgress<'a> {
+    /// Traversal is starting from these entries
+    Analyzing { entries: &'a [PathBuf] },
+    /// A file's imports were parsed; `files_parsed` counts every file so far
+    FileParsed { path: &'a Path, files_parsed: usize },
+    /// Traversal finished with this many local files besides the entries
+    DependenciesFound { count: usize },
+    /// Files outside the extension filter were left out of the bundle
+    ExtensionsFiltered {
+        included: usize,
+        extensions: &'a [String],
+    },
+    /// Unused declarations were left out of dependencies
+    Sliced { files: usize, omitted: usize },
+    /// Dependencies beyond the full depth were reduced to their signatures
+    SignaturesOnly { files: usize },
+    /// The token budget was applied
+    BudgetApplied(&'a BudgetReport),
+    /// Declarations of external packages were collected
+    ExternalApiCollected {
+        packages: usize,
+        files: usize,
+        omitted: usize,
+    },
+    /// The bundle is being rendered
+    Rendering,
+}
+
+/// Token cap for the external API surface when none is configured
+const DEFAULT_EXTERNAL_API_TOKENS: usize = 20_000;
+
+pub type ProgressCallback = Arc<dyn Fn(&BundleProgress<'_>) + Send + Sync>;
+
+/// Result of [`Bundler::bundle`]
+pub struct Bundle {
+    pub graph: DependencyGraph,
+    /// Contents of every file included in the bundle
+    pub files: HashMap<PathBuf, String>,
+    pub notes: BundleNotes,
+    pub budget: Option<BudgetReport>,
+    pub git_root: Option<PathBuf>,
+    pub format: OutputFormat,
+    pub rendered: String,
+}
+
+/// Builds dependency graphs and rendered bundles for a set of entry points.
+///
+/// Output, traversal and resolution options come from a [`PackletConfig`];
+/// anything left unset falls back to the same defaults as the CLI.
+#[derive(Clone)]
+pub struct Bundler {
+    entries: Vec<PathBuf>,
+    config: PackletConfig,
+    fs: Arc<dyn FileSystemProvider>,
+    adapter: Option<Arc<dyn LanguageAdapter>>,
+    parse_cache: Option<Arc<ParseCache>>,
+    tokenizer: Arc<dyn Tokenizer>,
+    git_root: Option<PathBuf>,
+    progress: Option<ProgressCallback>,
+}
+
+impl Bundler {
+    pub fn new<I, P>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            entries: entries.into_iter().map(Into::into).collect(),
+            config: PackletConfig::default(),
+            fs: Arc::new(CachedFileSystem::new(Box::new(LocalFileSystem))),
+            adapter: None,
+            parse_cache: None,
+            tokenizer: Arc::new(ApproxTokenizer),
+            git_root: None,
+            progress: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: PackletConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_file_system(mut self, fs: Arc<dyn FileSystemProvider>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Use this adapter instead of picking one from the first entry's extension
+    pub fn with_adapter(mut self, adapter: Arc<dyn LanguageAdapter>) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Reuse parsed imports through a persistent cache, which is saved after
+    /// every traversal
+    pub fn with_parse_cache(mut self, cache: Arc<ParseCache>) -> Self {
+        self.parse_cache = Some(cache);
+        self
+    }
+
+    /// Count tokens for `--max-tokens` with this tokenizer instead of the
+    /// built-in approximation
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Root that paths in the output are relative to (default: git root of the
+    /// first entry)
+    pub fn with_git_root(mut self, git_root: PathBuf) -> Self {
+        self.git_root = Some(git_root);
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.config.output.format = Some(format);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.config.output.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Add the type declarations of imported external packages
+    pub fn with_external_api(mut self, include: bool) -> Self {
+        self.config.output.external_api = Some(include);
+        self
+    }
+
+    /// Follow the imports of stylesheets and other parseable assets
+    pub fn with_assets(mut self, include: bool) -> Self {
+        self.config.traversal.include_assets = Some(include);
+        self
+    }
+
+    /// Keep only the declarations of dependencies that are used from the
+    /// entries
+    pub fn with_slice(mut self, slice: bool) -> Self {
+        self.config.output.slice = Some(slice);
+        self
+    }
+
+    /// Render dependencies beyond `full_depth` imports from the entries as
+    /// declarations only when `detail` is [`Detail::Signatures`]
+    pub fn with_detail(mut self, detail: Detail, full_depth: usize) -> Self {
+        self.config.output.detail = Some(detail);
+        self.config.output.full_depth = Some(full_depth);
+        self
+    }
+
+    pub fn with_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(&BundleProgress<'_>) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn entries(&self) -> &[PathBuf] {
+        &self.entries
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.config.output.format.unwrap_or(OutputFormat::Markdown)
+    }
+
+    /// Follows local imports from every entry point
+    pub async fn graph(&self) -> Result<DependencyGraph> {
+        let adapter = self.adapter()?;
+        let context = Arc::new(AnalysisContext {
+            fs: self.fs.clone(),
+        });
+
+        self.report(BundleProgress::Analyzing {
+            entries: &self.entries,
+        });
+
+        let timeout = Duration::from_secs(self.config.traversal.timeout.unwrap_or(120));
+        let graph = tokio::time::timeout(
+            timeout,
+            self.traverser()
+                .with_adapters(self.adapters())
+                .traverse_many(&self.entries, adapter, context),
+        )
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Traversal timeout after {} seconds. Try using --max-depth or --max-files to limit scope.",
+                timeout.as_secs()
+            )
+        })??;
+
+        if let Some(ref cache) = self.parse_cache {
+            if let Err(e) = cache.save() {
+                log::warn!("Could not save parse cache: {}", e);
+            }
+        }
+
+        let count = local_files(&graph).len() - graph.entry_points.len();
+        self.report(BundleProgress::DependenciesFound { count });
+
+        Ok(graph)
+    }
+
+    /// Builds the graph, reads every included file, applies the token budget
+    /// and renders the bundle in the configured format
+    pub async fn bundle(&self) -> Result<Bundle> {
+        let graph = self.graph().await?;
+
+        let mut files_to_read = local_files(&graph);
+
+        // Files outside the extension filter stay in the tree but are not
+        // emitted. Entries were asked for explicitly and are always kept.
+        let extensions = self.config.traversal.extensions.clone().unwrap_or_default();
+        if !extensions.is_empty() {
+            files_to_read.retain(|p| graph.is_entry(p) || matches_extensions(p, &extensions));
+            self.report(BundleProgress::ExtensionsFiltered {
+                included: files_to_read.len(),
+                extensions: &extensions,
+            });
+        }
+
+        let mut files = HashMap::new();
+        for file_path in files_to_read {
+            match self.fs.read_file(&file_path).await {
+                Ok(content) => {
+                    files.insert(file_path, content);
+                }
+                // Images, fonts and other binary assets are listed but not emitted
+                Err(_) if graph.assets.contains(&file_path) => {
+                    log::debug!("Skipping unreadable asset: {}", file_path.display());
+                }
+                Err(_) => log::warn!("Could not read file: {}", file_path.display()),
+            }
+        }
+
+        let git_root = match self.git_root {
+            Some(ref root) => Some(root.clone()),
+            None => match self.entries.first() {
+                Some(entry) => find_git_root(entry).await,
+                None => None,
+            },
+        };
+
+        let mut notes = BundleNotes::default();
+        if self.config.output.slice.unwrap_or(false) {
+            let (files, omitted) = slice_files(&graph, &mut files, &mut notes);
+            self.report(BundleProgress::Sliced { files, omitted });
+        }
+
+        if self.config.output.detail == Some(Detail::Signatures) {
+            let full_depth = self.config.output.full_depth.unwrap_or(0);
+            let files = reduce_to_signatures(&graph, &mut files, full_depth, &mut notes);
+            self.report(BundleProgress::SignaturesOnly { files });
+        }
+
+        let budget = self.config.output.max_tokens.map(|max_tokens| {
+            apply_token_budget(
+                &graph,
+                &mut files,
+                max_tokens,
+                &*self.tokenizer,
+                git_root.as_deref(),
+                &mut notes,
+            )
+        });
+        if let Some(ref report) = budget {
+            self.report(BundleProgress::BudgetApplied(report));
+        }
+
+        if self.config.output.external_api.unwrap_or(false) {
+            let external_api = self.external_api(&graph).await;
+            self.report(BundleProgress::ExternalApiCollected {
+                packages: external_api.packages.len(),
+                files: external_api.packages.values().map(Vec::len).sum(),
+                omitted: external_api.omitted.len(),
+            });
+            notes.external_api = Some(external_api);
+        }
+
+        let format = self.format();
+        let formatter: Box<dyn OutputFormatter> = match format {
+            OutputFormat::Markdown => Box::new(MarkdownFormatter),
+            OutputFormat::Xml => Box::new(XmlFormatter),
+        };
+
+        self.report(BundleProgress::Rendering);
+        let rendered = formatter.format_with_notes(&graph, &files, git_root.as_deref(), &notes)?;
+
+        Ok(Bundle {
+            graph,
+            files,
+            notes,
+            budget,
+            git_root,
+            format,
+            rendered,
+        })
+    }
+
+    /// Reads the declarations of the external packages `graph` imports from.
+    /// Files are taken one level at a time across packages, so every entry
+    /// declaration is included before the files they reference.
+    async fn external_api(&self, graph: &DependencyGraph) -> ExternalApi {
+        let imports = graph
+            .external_imports
+            .iter()
+            .flat_map(|(from, imports)| imports.iter().map(move |i| (from.as_path(), i)));
+        let declarations = DeclarationCollector::new()
+            .collect(imports, &*self.fs)
+            .await;
+
+        let max_tokens = self
+            .config
+            .output
+            .external_api_max_tokens
+            .unwrap_or(DEFAULT_EXTERNAL_API_TOKENS);
+        let mut used_tokens = 0;
+        let mut external_api = ExternalApi::default();
+        let levels = declarations.values().map(Vec::len).max().unwrap_or(0);
+
+        for level in 0..levels {
+            for (package, files) in &declarations {
+                let Some(path) = files.get(level) else {
+                    continue;
+                };
+                let Ok(content) = self.fs.read_file(path).await else {
+                    log::warn!("Could not read file: {}", path.display());
+                    continue;
+                };
+
+                let tokens = self.tokenizer.count_tokens(&content);
+                if used_tokens + tokens > max_tokens {
+                    external_api.omitted.push(path.clone());
+                    continue;
+                }
+                used_tokens += tokens;
+                external_api
+                    .packages
+                    .entry(package.clone())
+                    .or_default()
+                    .push((path.clone(), content));
+            }
+        }
+
+        external_api
+    }
+
+    /// The configured adapter, or the one for the first entry's extension.
+    /// Every entry must be parseable by it or by one of the built-in adapters.
+    fn adapter(&self) -> Result<Arc<dyn LanguageAdapter>> {
+        let entry_file = self
+            .entries
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No entry points given"))?;
+
+        let adapter = match self.adapter {
+            Some(ref adapter) => adapter.clone(),
+            None => {
+                let extension = entry_file
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("");
+                let adapter: Arc<dyn LanguageAdapter> = Arc::from(
+                    get_adapter_for_extension(extension, &self.config).ok_or_else(|| {
+                        anyhow::anyhow!("Unsupported file type: {}", entry_file.display())
+                    })?,
+                );
+                adapter
+            }
+        };
+
+        // Entries in other languages are parsed by their own adapters
+        let adapters = get_adapters(&self.config);
+        if let Some(other) = self
+            .entries
+            .iter()
+            .find(|e| !adapter.can_parse_file(e) && !adapters.iter().any(|a| a.can_parse_file(e)))
+        {
+            return Err(anyhow::anyhow!(
+                "Unsupported file type: {}",
+                other.display()
+            ));
+        }
+
+        Ok(self.cached(adapter))
+    }
+
+    /// Every built-in adapter, so files in other languages than the entries
+    /// are parsed by their own adapter
+    fn adapters(&self) -> Vec<Arc<dyn LanguageAdapter>> {
+        get_adapters(&self.config)
+            .into_iter()
+            .map(|adapter| self.cached(Arc::from(adapter)))
+            .collect()
+    }
+
+    fn cached(&self, adapter: Arc<dyn LanguageAdapter>) -> Arc<dyn LanguageAdapter> {
+        match self.parse_cache {
+            Some(ref cache) => Arc::new(CachedAdapter::new(adapter, cache.clone())),
+            None => adapter,
+        }
+    }
+
+    fn traverser(&self) -> DependencyTraverser {
+        let traversal = &self.config.traversal;
+        let mut traverser = DependencyTraverser::new().with_max_depth(traversal.max_depth);
+
+        if let Some(max_files) = traversal.max_files {
+            traverser = traverser.with_max_files(max_files);
+        }
+
+        if let Some(ref exclude) = traversal.exclude {
+            traverser = traverser.with_exclude_patterns(exclude.clone());
+        }
+
+        if traversal.strict_extensions.unwrap_or(false) {
+            traverser =
+                traverser.with_extension_filter(traversal.extensions.clone().unwrap_or_default());
+        }
+
+        if let Some(imports) = traversal.imports {
+            traverser = traverser.with_import_filter(imports);
+        }
+
+        traverser = traverser.with_assets(traversal.include_assets.unwrap_or(false));
+
+        if let Some(ref progress) = self.progress {
+            let progress = progress.clone();
+            let files_parsed = AtomicUsize::new(0);
+            traverser = traverser.with_progress(Arc::new(move |path| {
+                let files_parsed = files_parsed.fetch_add(1, Ordering::Relaxed) + 1;
+                progress(&BundleProgress::FileParsed { path, files_parsed });
+            }));
+        }
+
+        traverser
+    }
+
+    fn report(&self, event: BundleProgress<'_>) {
+        if let Some(ref progress) = self.progress {
+            progress(&event);
+        }
+    }
+}
+
+/// Entries followed by every other local file in the graph, deduplicated
+fn local_files(graph: &DependencyGraph) -> Vec<PathBuf> {
+    let mut files = graph.entry_points.clone();
+    let mut deps: Vec<PathBuf> = graph
+        .adj_list
+        .iter()
+        .flat_map(|(from, deps)| std::iter::once(from).chain(deps.iter().map(|(to, _)| to)))
+        .filter(|path| !graph.is_entry(path))
+        .cloned()
+        .collect();
+    deps.sort();
+    deps.dedup();
+    files.extend(deps);
+    files
+}
+
+/// Replaces the JavaScript and TypeScript files more than `full_depth` imports
+/// away from every entry with their declarations, noting each as abbreviated.
+/// Returns the number of files reduced.
+fn reduce_to_signatures(
+    graph: &DependencyGraph,
+    files: &mut HashMap<PathBuf, String>,
+    full_depth: usize,
+    notes: &mut BundleNotes,
+) -> usize {
+    let distances = distances_from_entry(graph);
+    let mut reduced = 0;
+
+    for (path, content) in files.iter_mut() {
+        let beyond = distances
+            .get(path)
+            .is_some_and(|distance| *distance > full_depth);
+        let reducible = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| SLICEABLE_EXTENSIONS.contains(&e));
+        if !beyond || !reducible || graph.is_entry(path) {
+            continue;
+        }
+
+        match signatures(path, content) {
+            Ok(declarations) if declarations.trim() != content.trim() => {
+                *content = declarations;
+                notes.abbreviate(path, "signatures only");
+                reduced += 1;
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Keeping {} in full: {}", path.display(), e),
+        }
+    }
+
+    reduced
+}
+
+/// Replaces dependencies with the parts their importers use, noting each
+/// sliced file as abbreviated. Entries are always kept whole. Returns the
+/// number of files sliced and of declarations left out.
+///
+/// Slicing a file can drop the last use of one of its imports, which in turn
+/// lets more of the imported file go, so this repeats until nothing changes.
+fn slice_files(
+    graph: &DependencyGraph,
+    files: &mut HashMap<PathBuf, String>,
+    notes: &mut BundleNotes,
+) -> (usize, usize) {
+    const MAX_PASSES: usize = 10;
+
+    let mut live_locals = HashMap::new();
+    let mut sliced = HashMap::new();
+    for _ in 0..MAX_PASSES {
+        let usage = used_exports(graph, &live_locals);
+        sliced = HashMap::new();
+
+        for (path, content) in files.iter() {
+            let Some(Usage::Names(used)) = usage.get(path) else {
+                continue;
+            };
+            let sliceable = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SLICEABLE_EXTENSIONS.contains(&e));
+            if !sliceable || graph.is_entry(path) {
+                continue;
+            }
+
+            match slice_module(path, content, used) {
+                Ok(Some(module)) => {
+                    sliced.insert(path.clone(), module);
+                }
+                Ok(None) => {}
+                Err(e) => log::debug!("Not slicing {}: {}", path.display(), e),
+            }
+        }
+
+        let next_live: HashMap<PathBuf, HashSet<String>> = sliced
+            .iter()
+            .map(|(path, module)| (path.clone(), module.referenced.clone()))
+            .collect();
+        if next_live == live_locals {
+            break;
+        }
+        live_locals = next_live;
+    }
+
+    let mut omitted = 0;
+    let sliced_files = sliced.len();
+    for (path, module) in sliced {
+        notes.abbreviate(
+            &path,
+            format!(
+                "sliced, {} unused declarations omitted",
+                module.omitted.len()
+            ),
+        );
+        omitted += module.omitted.len();
+        files.insert(path, module.content);
+    }
+
+    (sliced_files, omitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_bundle_reports_progress_and_renders_fixture() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/bundle");
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+
+        let bundle = Bundler::new([root.join("main.ts")])
+            .with_git_root(root.clone())
+            .with_progress(move |event| {
+                let event = match event {
+                    BundleProgress::Analyzing { entries } => format!("analyzing {}", entries.len()),
+                    BundleProgress::FileParsed { path, files_parsed } => format!(
+                        "parsed {} {}",
+                        path.file_name().unwrap().to_string_lossy(),
+                        files_parsed
+                    ),
+                    BundleProgress::DependenciesFound { count } => format!("found {}", count),
+                    BundleProgress::Rendering => "rendering".to_string(),
+                    other => format!("{:?}", other),
+                };
+                recorded.lock().unwrap().push(event);
+            })
+            .bundle()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "analyzing 1",
+                "parsed main.ts 1",
+                "parsed greet.ts 2",
+                "found 1",
+                "rendering",
+            ]
+        );
+        assert_eq!(bundle.files.len(), 2);
+
+        // Everything after the generation timestamp is stable
+        let body = &bundle.rendered[bundle.rendered.find("**Entry:**").unwrap()..];
+        let expected = r#"**Entry:** `main.ts`
+
+## Dependency Tree
+
+```
+main.ts
+└── greet.ts
+```
+
+## File Contents
+
+### `greet.ts`
+
+```ts
+export function greet(name: string): string {
+  return `Hello, ${name}`;
+}
+
+```
+
+### `main.ts`
+
+```ts
+import { greet } from './greet';
+
+console.log(greet('world'));
+
+```
+"#;
+        assert_eq!(body.trim_end(), expected.trim_end());
+    }
+
+    #[tokio::test]
+    async fn test_extension_filter_keeps_entries() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/bundle");
+        let mut config = PackletConfig::default();
+        config.traversal.extensions = Some(vec!["css".to_string()]);
+
+        let bundle = Bundler::new([root.join("main.ts")])
+            .with_config(config)
+            .with_git_root(root.clone())
+            .bundle()
+            .await
+            .unwrap();
+
+        // The filtered dependency stays in the tree but is not emitted
+        assert!(bundle
+            .graph
+            .reachable_from(&root.join("main.ts"))
+            .contains(&root.join("greet.ts")));
+        let files: Vec<&PathBuf> = bundle.files.keys().collect();
+        assert_eq!(files, vec![&root.join("main.ts")]);
+    }
+
+    /// Counts every byte as a token
+    struct ByteTokenizer;
+
+    impl Tokenizer for ByteTokenizer {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.len()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_uses_custom_tokenizer() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/bundle");
+        let bundle = |tokenizer: Option<Arc<dyn Tokenizer>>| {
+            let mut bundler = Bundler::new([root.join("main.ts")])
+                .with_git_root(root.clone())
+                .with_max_tokens(100);
+            if let Some(tokenizer) = tokenizer {
+                bundler = bundler.with_tokenizer(tokenizer);
+            }
+            async move { bundler.bundle().await.unwrap() }
+        };
+
+        // Both files fit by the approximation, but only the entry by bytes
+        assert_eq!(bundle(None).await.files.len(), 2);
+        let bundle = bundle(Some(Arc::new(ByteTokenizer))).await;
+        assert_eq!(bundle.files.len(), 1);
+        assert!(bundle.notes.is_omitted(&root.join("greet.ts")));
+    }
+}
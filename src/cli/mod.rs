@@ -1,10 +1,16 @@
+use crate::bundler::{BundleProgress, Bundler};
+use crate::config::{OutputConfig, PackletConfig, TraversalConfig};
 use crate::core::fs::FileSystemProvider;
+use crate::core::traverser::ImportFilter;
 use crate::{core, output};
 use clap::{Parser, Subcommand, ValueEnum};
 use path_absolutize::Absolutize;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+pub use crate::output::{Detail, OutputFormat};
+
 #[derive(Parser)]
 #[command(name = "packlet")]
 #[command(about = "Lightning-fast local dependency bundler")]
@@ -15,20 +21,26 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Config file to use instead of the nearest packlet.toml
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
+
+    /// Reuse parsed imports from previous runs (stored in .packlet/cache)
+    #[arg(long, global = true)]
+    pub cache: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Bundle dependencies from an entry file
+    /// Bundle dependencies from one or more entry files
     Bundle {
-        /// Entry file path
-        file: PathBuf,
+        /// Entry file paths or glob patterns (e.g. 'src/pages/*.tsx')
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
-        /// Output format
-        #[arg(short, long, value_enum, default_value = "markdown")]
-        format: OutputFormat,
+        /// Output format (default: markdown)
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
 
         /// Output file path (auto-generated if not specified)
         #[arg(short, long)]
@@ -46,22 +58,64 @@ pub enum Commands {
         #[arg(long)]
         timeout: Option<u64>,
 
-        /// Include only specific file extensions
+        /// Include only specific file extensions (other files are still traversed)
         #[arg(long, value_delimiter = ',')]
         extensions: Option<Vec<String>>,
 
+        /// Don't traverse through files excluded by --extensions either
+        #[arg(long)]
+        strict_extensions: bool,
+
+        /// Approximate token budget; files farthest from the entry are dropped first
+        #[arg(long)]
+        max_tokens: Option<usize>,
+
+        /// Append the type declarations of imported external packages
+        #[arg(long)]
+        external_api: bool,
+
+        /// Keep only the declarations of dependencies that the entries use
+        #[arg(long)]
+        slice: bool,
+
+        /// How much of each dependency to include (default: full)
+        #[arg(long, value_enum)]
+        detail: Option<Detail>,
+
+        /// Import distance up to which --detail signatures keeps files in full (default: 0)
+        #[arg(long)]
+        full_depth: Option<usize>,
+
         /// Exclude patterns (gitignore syntax)
         #[arg(long, value_delimiter = ',')]
         exclude: Option<Vec<String>>,
+
+        /// Follow only imports that exist at runtime, skipping type-only ones
+        #[arg(long, conflicts_with = "types_only")]
+        runtime_only: bool,
+
+        /// Follow only type-only imports (`import type`, `import("x").T`, ...)
+        #[arg(long)]
+        types_only: bool,
+
+        /// Follow imports inside assets such as stylesheets imported from scripts
+        #[arg(long)]
+        include_assets: bool,
     },
 
     /// Visualize dependency graph
     Graph {
-        file: PathBuf,
+        /// Entry file paths or glob patterns
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
         #[arg(long, value_enum, default_value = "dot")]
         format: GraphFormat,
 
+        /// Group DOT nodes into clusters by directory
+        #[arg(long)]
+        cluster: bool,
+
         /// Maximum traversal depth (default: 50)
         #[arg(long)]
         max_depth: Option<usize>,
@@ -77,28 +131,69 @@ pub enum Commands {
         /// Exclude patterns (gitignore syntax)
         #[arg(long, value_delimiter = ',')]
         exclude: Option<Vec<String>>,
+
+        /// Follow only imports that exist at runtime, skipping type-only ones
+        #[arg(long, conflicts_with = "types_only")]
+        runtime_only: bool,
+
+        /// Follow only type-only imports (`import type`, `import("x").T`, ...)
+        #[arg(long)]
+        types_only: bool,
+
+        /// Follow imports inside assets such as stylesheets imported from scripts
+        #[arg(long)]
+        include_assets: bool,
     },
-}
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
-pub enum OutputFormat {
-    Markdown,
-    Xml,
+    /// List every file that transitively imports a file
+    Dependents {
+        file: PathBuf,
+
+        /// Project root to scan (default: git root of the file)
+        #[arg(long)]
+        root: Option<PathBuf>,
+
+        #[arg(long, value_enum, default_value = "text")]
+        format: DependentsFormat,
+
+        /// Timeout in seconds (default: 120)
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Exclude patterns (gitignore syntax)
+        #[arg(long, value_delimiter = ',')]
+        exclude: Option<Vec<String>>,
+    },
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
 pub enum GraphFormat {
+    /// Graphviz DOT digraph
     Dot,
     Json,
+    /// ASCII dependency tree
+    Tree,
 }
 
-/// Generate a default output filename based on the input file and format
-fn generate_output_filename(input_file: &Path, format: OutputFormat) -> PathBuf {
-    // Get the input filename without extension
-    let stem = input_file
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("output");
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
+pub enum DependentsFormat {
+    /// One line per dependent with its import chain
+    Text,
+    Json,
+}
+
+/// Generate a default output filename based on the input files and format
+fn generate_output_filename(input_files: &[PathBuf], format: OutputFormat) -> PathBuf {
+    // Single entry: the filename without extension, otherwise the name of the
+    // directory the entries share
+    let stem = match input_files {
+        [input_file] => input_file
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned()),
+        _ => common_parent(input_files)
+            .and_then(|dir| dir.file_name().map(|s| s.to_string_lossy().into_owned())),
+    }
+    .unwrap_or_else(|| "output".to_string());
 
     // Determine the appropriate extension based on format
     let extension = match format {
@@ -113,114 +208,225 @@ fn generate_output_filename(input_file: &Path, format: OutputFormat) -> PathBuf
     PathBuf::from(filename)
 }
 
+/// Deepest directory containing all of the given paths
+fn common_parent(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut common = paths.first()?.parent()?.to_path_buf();
+    for path in &paths[1..] {
+        while !path.starts_with(&common) {
+            common = common.parent()?.to_path_buf();
+        }
+    }
+    Some(common)
+}
+
+/// Expand glob patterns among the entry arguments and make every entry absolute
+fn resolve_entries(patterns: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    for pattern in patterns {
+        let pattern_str = pattern.to_string_lossy();
+        if !pattern_str.contains(['*', '?', '[']) {
+            entries.push(pattern.absolutize()?.to_path_buf());
+            continue;
+        }
+
+        let mut matched: Vec<PathBuf> = glob::glob(&pattern_str)
+            .map_err(|e| anyhow::anyhow!("Invalid entry pattern '{}': {}", pattern_str, e))?
+            .filter_map(|p| p.ok())
+            .filter(|p| p.is_file())
+            .collect();
+        if matched.is_empty() {
+            return Err(anyhow::anyhow!("No files match '{}'", pattern_str));
+        }
+        matched.sort();
+        for path in matched {
+            entries.push(path.absolutize()?.to_path_buf());
+        }
+    }
+    // The same file may be named twice, or matched by several patterns
+    let mut seen = HashSet::new();
+    entries.retain(|entry| seen.insert(entry.clone()));
+    Ok(entries)
+}
+
+/// Import filter selected by `--runtime-only` / `--types-only`, if any
+fn import_filter(runtime_only: bool, types_only: bool) -> Option<ImportFilter> {
+    if runtime_only {
+        Some(ImportFilter::Runtime)
+    } else if types_only {
+        Some(ImportFilter::Types)
+    } else {
+        None
+    }
+}
+
+/// Opens the persistent parse cache when enabled by `--cache` or the config.
+/// It lives in `.packlet/cache` under the project root unless configured.
+fn open_parse_cache(
+    config: &PackletConfig,
+    enabled: bool,
+    project_root: &Path,
+) -> Option<Arc<core::cache::ParseCache>> {
+    if !enabled && !config.cache.enabled.unwrap_or(false) {
+        return None;
+    }
+
+    let dir = config
+        .cache
+        .dir
+        .clone()
+        .unwrap_or_else(|| project_root.join(core::cache::DEFAULT_CACHE_DIR));
+    // Resolution settings are part of the cache identity
+    let settings = format!(
+        "{:?} {:?} {:?} {:?}",
+        config.javascript, config.python, config.go, config.css
+    );
+    Some(Arc::new(core::cache::ParseCache::open(&dir, &settings)))
+}
+
+fn with_parse_cache(
+    adapter: Arc<dyn core::language::LanguageAdapter>,
+    cache: Option<&Arc<core::cache::ParseCache>>,
+) -> Arc<dyn core::language::LanguageAdapter> {
+    match cache {
+        Some(cache) => Arc::new(core::cache::CachedAdapter::new(adapter, cache.clone())),
+        None => adapter,
+    }
+}
+
+fn save_parse_cache(cache: Option<Arc<core::cache::ParseCache>>) {
+    if let Some(cache) = cache {
+        if let Err(e) = cache.save() {
+            log::warn!("Could not save parse cache: {}", e);
+        }
+    }
+}
+
+/// Bundler for the CLI's entries, using the parse cache when enabled
+async fn build_bundler(entries: Vec<PathBuf>, config: PackletConfig, use_cache: bool) -> Bundler {
+    let project_root = core::fs::find_git_root(&entries[0])
+        .await
+        .unwrap_or_else(|| entries[0].parent().unwrap_or(Path::new("/")).to_path_buf());
+
+    let mut bundler = Bundler::new(entries);
+    if let Some(cache) = open_parse_cache(&config, use_cache, &project_root) {
+        bundler = bundler.with_parse_cache(cache);
+    }
+    bundler.with_config(config)
+}
+
 pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let config_path = cli.config;
+    let use_cache = cli.cache;
     match cli.command {
         Commands::Bundle {
-            file,
+            files,
             format,
             output,
             max_depth,
             max_files,
             timeout,
+            extensions,
+            strict_extensions,
+            max_tokens,
+            external_api,
+            slice,
+            detail,
+            full_depth,
             exclude,
-            ..
+            runtime_only,
+            types_only,
+            include_assets,
         } => {
-            let entry_file = file.absolutize()?.to_path_buf();
+            let entries = resolve_entries(&files)?;
+            let config = PackletConfig::load(&entries[0], config_path.as_deref())
+                .await?
+                .merge(PackletConfig {
+                    output: OutputConfig {
+                        format,
+                        max_tokens,
+                        external_api: external_api.then_some(true),
+                        slice: slice.then_some(true),
+                        detail,
+                        full_depth,
+                        ..Default::default()
+                    },
+                    traversal: TraversalConfig {
+                        max_depth,
+                        max_files,
+                        timeout,
+                        exclude,
+                        extensions,
+                        strict_extensions: strict_extensions.then_some(true),
+                        imports: import_filter(runtime_only, types_only),
+                        include_assets: include_assets.then_some(true),
+                    },
+                    ..Default::default()
+                });
+
+            let bundler = build_bundler(entries, config, use_cache)
+                .await
+                .with_progress(|event| match event {
+                    BundleProgress::Analyzing { .. } => println!("Analyzing dependencies..."),
+                    BundleProgress::DependenciesFound { count } => {
+                        println!("Found {} local dependencies", count)
+                    }
+                    BundleProgress::ExtensionsFiltered {
+                        included,
+                        extensions,
+                    } => println!(
+                        "Including {} files matching --extensions {}",
+                        included,
+                        extensions.join(",")
+                    ),
+                    BundleProgress::BudgetApplied(report) => println!(
+                        "Token budget: ~{} of {} tokens used, {} files included ({} truncated), {} omitted",
+                        report.used_tokens,
+                        report.max_tokens,
+                        report.included,
+                        report.truncated,
+                        report.omitted
+                    ),
+                    BundleProgress::Sliced { files, omitted } => println!(
+                        "Sliced {} files, omitting {} unused declarations",
+                        files, omitted
+                    ),
+                    BundleProgress::SignaturesOnly { files } => {
+                        println!("Reduced {} files to their signatures", files)
+                    }
+                    BundleProgress::ExternalApiCollected {
+                        packages,
+                        files,
+                        omitted,
+                    } => println!(
+                        "Including {} declaration files from {} external packages ({} left out by the size cap)",
+                        files, packages, omitted
+                    ),
+                    BundleProgress::Rendering => println!("Generating output..."),
+                    BundleProgress::FileParsed { .. } => {}
+                });
+            let format = bundler.format();
 
             // Determine the output path - either provided or auto-generated
-            let output_path = output.unwrap_or_else(|| generate_output_filename(&file, format));
+            let output_path =
+                output.unwrap_or_else(|| generate_output_filename(bundler.entries(), format));
 
             // Log what we're doing
             log::info!(
-                "Bundling {} into {}...",
-                entry_file.display(),
+                "Bundling {} entries into {}...",
+                bundler.entries().len(),
                 output_path.display()
             );
 
             // Create a more user-friendly console message
-            println!("Bundling: {}", entry_file.display());
-            println!("Output format: {:?}", format);
-
-            let fs_provider = Arc::new(core::fs::CachedFileSystem::new(Box::new(
-                core::fs::LocalFileSystem,
-            )));
-
-            let extension = entry_file
-                .extension()
-                .and_then(|s| s.to_str())
-                .unwrap_or("");
-            let adapter = Arc::from(
-                core::language::get_adapter_for_extension(extension).ok_or_else(|| {
-                    anyhow::anyhow!("Unsupported file type: {}", entry_file.display())
-                })?,
-            );
-
-            let context = Arc::new(core::language::AnalysisContext {
-                fs: fs_provider.clone(),
-            });
-
-            let mut traverser =
-                core::traverser::DependencyTraverser::new().with_max_depth(max_depth);
-
-            if let Some(max_files_limit) = max_files {
-                traverser = traverser.with_max_files(max_files_limit);
+            for entry in bundler.entries() {
+                println!("Bundling: {}", entry.display());
             }
+            println!("Output format: {:?}", format);
 
-            if let Some(exclude_patterns) = exclude {
-                traverser = traverser.with_exclude_patterns(exclude_patterns);
-            }
-
-            println!("Analyzing dependencies...");
-
-            let timeout_duration = std::time::Duration::from_secs(timeout.unwrap_or(120));
-            let graph = tokio::time::timeout(
-                timeout_duration,
-                traverser.traverse(&entry_file, adapter, context)
-            )
-            .await
-            .map_err(|_| anyhow::anyhow!(
-                "Traversal timeout after {} seconds. Try using --max-depth or --max-files to limit scope.",
-                timeout_duration.as_secs()
-            ))??;
-
-            let mut file_contents = std::collections::HashMap::new();
-            let mut files_to_read = vec![graph.entry_point.clone()];
-            for (from, deps) in &graph.adj_list {
-                files_to_read.push(from.clone());
-                for (to, _) in deps {
-                    files_to_read.push(to.clone());
-                }
-            }
-            files_to_read.sort();
-            files_to_read.dedup();
-
-            // Show how many files we found
-            println!("Found {} local dependencies", files_to_read.len() - 1);
-
-            for file_path in files_to_read {
-                if let Ok(content) = fs_provider.read_file(&file_path).await {
-                    file_contents.insert(file_path, content);
-                } else {
-                    log::warn!("Could not read file: {}", file_path.display());
-                }
-            }
-
-            let formatter: Box<dyn output::OutputFormatter> = match format {
-                OutputFormat::Markdown => Box::new(output::MarkdownFormatter),
-                OutputFormat::Xml => {
-                    return Err(anyhow::anyhow!("XML output format is not yet supported"));
-                }
-            };
-
-            println!("Generating output...");
-
-            // Find git root for relative path formatting
-            let git_root = core::fs::find_git_root(&entry_file).await;
-            let output_str =
-                formatter.format_with_git_root(&graph, &file_contents, git_root.as_deref())?;
+            let bundle = bundler.bundle().await?;
 
             // Always write to a file
-            tokio::fs::write(&output_path, output_str).await?;
+            tokio::fs::write(&output_path, bundle.rendered).await?;
 
             // Calculate file size for user feedback
             let metadata = tokio::fs::metadata(&output_path).await?;
@@ -235,88 +441,184 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
             println!("Tip: Use --output to specify a custom output location");
         }
         Commands::Graph {
-            file,
+            files,
             format,
+            cluster,
             max_depth,
             max_files,
             timeout,
             exclude,
+            runtime_only,
+            types_only,
+            include_assets,
         } => {
-            let entry_file = file.absolutize()?.to_path_buf();
+            let entries = resolve_entries(&files)?;
+            let config = PackletConfig::load(&entries[0], config_path.as_deref())
+                .await?
+                .merge(PackletConfig {
+                    traversal: TraversalConfig {
+                        max_depth,
+                        max_files,
+                        timeout,
+                        exclude,
+                        imports: import_filter(runtime_only, types_only),
+                        include_assets: include_assets.then_some(true),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                });
 
             // Find git root for relative path formatting
-            let git_root = core::fs::find_git_root(&entry_file).await;
-            let display_path = if let Some(ref root) = git_root {
-                entry_file
-                    .strip_prefix(root)
-                    .map(|p| p.display().to_string())
-                    .unwrap_or_else(|_| entry_file.display().to_string())
-            } else {
-                entry_file.display().to_string()
-            };
+            let git_root = core::fs::find_git_root(&entries[0]).await;
+            let display_paths: Vec<String> = entries
+                .iter()
+                .map(|entry| match git_root {
+                    Some(ref root) => entry
+                        .strip_prefix(root)
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|_| entry.display().to_string()),
+                    None => entry.display().to_string(),
+                })
+                .collect();
+
+            // Progress goes to stderr so the graph itself can be piped
+            eprintln!("Generating graph for {}...", display_paths.join(", "));
+
+            let graph = build_bundler(entries, config, use_cache)
+                .await
+                .with_progress(|event| match event {
+                    BundleProgress::Analyzing { .. } => eprintln!("Analyzing dependencies..."),
+                    BundleProgress::DependenciesFound { count } => {
+                        eprintln!("Found {} local dependencies", count)
+                    }
+                    _ => {}
+                })
+                .graph()
+                .await?;
 
-            println!("Generating graph for {}...", display_path);
+            match format {
+                GraphFormat::Dot => {
+                    let formatter = output::DotFormatter::new().with_clusters(cluster);
+                    let dot_output = formatter.format_graph(&graph, git_root.as_deref())?;
+                    print!("{}", dot_output);
+                }
+                GraphFormat::Tree => {
+                    // Use the existing tree rendering logic from MarkdownFormatter
+                    let formatter = output::MarkdownFormatter;
+                    let tree_output =
+                        formatter.format_tree_only_with_git_root(&graph, git_root.as_deref())?;
+                    println!("\n{}", tree_output);
+                }
+                GraphFormat::Json => {
+                    // JSON format for programmatic use
+                    let json_output = serde_json::to_string_pretty(&graph)?;
+                    println!("{}", json_output);
+                }
+            }
+        }
+        Commands::Dependents {
+            file,
+            root,
+            format,
+            timeout,
+            exclude,
+        } => {
+            let target = file.absolutize()?.to_path_buf();
+            let config = PackletConfig::load(&target, config_path.as_deref()).await?;
+
+            let git_root = core::fs::find_git_root(&target).await;
+            let root = match root {
+                Some(root) => root.absolutize()?.to_path_buf(),
+                None => git_root.clone().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "{} is not inside a git repository, use --root to choose the project root",
+                        target.display()
+                    )
+                })?,
+            };
 
             let fs_provider = Arc::new(core::fs::CachedFileSystem::new(Box::new(
                 core::fs::LocalFileSystem,
             )));
+            let target = fs_provider.canonicalize(&target).await?;
 
-            let extension = entry_file
-                .extension()
-                .and_then(|s| s.to_str())
-                .unwrap_or("");
-            let adapter = Arc::from(
-                core::language::get_adapter_for_extension(extension).ok_or_else(|| {
-                    anyhow::anyhow!("Unsupported file type: {}", entry_file.display())
-                })?,
-            );
+            let extension = target.extension().and_then(|s| s.to_str()).unwrap_or("");
+            let parse_cache = open_parse_cache(&config, use_cache, &root);
+            if core::language::get_adapter_for_extension(extension, &config).is_none() {
+                anyhow::bail!("Unsupported file type: {}", target.display());
+            }
+            let adapters: Vec<Arc<dyn core::language::LanguageAdapter>> =
+                core::language::get_adapters(&config)
+                    .into_iter()
+                    .map(|adapter| with_parse_cache(Arc::from(adapter), parse_cache.as_ref()))
+                    .collect();
 
             let context = Arc::new(core::language::AnalysisContext {
                 fs: fs_provider.clone(),
             });
 
-            let mut traverser =
-                core::traverser::DependencyTraverser::new().with_max_depth(max_depth);
-
-            if let Some(max_files_limit) = max_files {
-                traverser = traverser.with_max_files(max_files_limit);
-            }
-
-            if let Some(exclude_patterns) = exclude {
-                traverser = traverser.with_exclude_patterns(exclude_patterns);
-            }
-
-            println!("Analyzing dependencies...");
+            eprintln!("Scanning {}...", root.display());
 
-            let timeout_duration = std::time::Duration::from_secs(timeout.unwrap_or(120));
-            let graph = tokio::time::timeout(
+            let exclude = exclude
+                .or_else(|| config.traversal.exclude.clone())
+                .unwrap_or_default();
+            let timeout_duration =
+                std::time::Duration::from_secs(timeout.or(config.traversal.timeout).unwrap_or(120));
+            let project = tokio::time::timeout(
                 timeout_duration,
-                traverser.traverse(&entry_file, adapter, context)
+                core::dependents::ProjectGraph::scan(&root, adapters, context, &exclude),
             )
             .await
-            .map_err(|_| anyhow::anyhow!(
-                "Traversal timeout after {} seconds. Try using --max-depth or --max-files to limit scope.",
-                timeout_duration.as_secs()
-            ))??;
-
-            let dep_count = graph
-                .adj_list
-                .values()
-                .map(|deps| deps.len())
-                .sum::<usize>();
-            println!("Found {} local dependencies", dep_count);
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Scan timeout after {} seconds. Try using --root or --exclude to limit scope.",
+                    timeout_duration.as_secs()
+                )
+            })??;
+            save_parse_cache(parse_cache);
+
+            let dependents = project.dependents_of(&target);
+            eprintln!(
+                "Scanned {} files, found {} dependents",
+                project.files_scanned,
+                dependents.len()
+            );
+
+            let display_root = git_root.as_deref().unwrap_or(&project.root);
+            let relative = |path: &Path| {
+                path.strip_prefix(display_root)
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|_| path.display().to_string())
+            };
 
             match format {
-                GraphFormat::Dot => {
-                    // Use the existing tree rendering logic from MarkdownFormatter
-                    let formatter = output::MarkdownFormatter;
-                    let tree_output =
-                        formatter.format_tree_only_with_git_root(&graph, git_root.as_deref())?;
-                    println!("\n{}", tree_output);
+                DependentsFormat::Text => {
+                    for dependent in &dependents {
+                        let chain: Vec<String> =
+                            dependent.chain.iter().map(|p| relative(p)).collect();
+                        println!("{}", relative(&dependent.path));
+                        println!("    {}", chain.join(" -> "));
+                    }
                 }
-                GraphFormat::Json => {
-                    // JSON format for programmatic use
-                    let json_output = serde_json::to_string_pretty(&graph)?;
+                DependentsFormat::Json => {
+                    let entries: Vec<serde_json::Value> = dependents
+                        .iter()
+                        .map(|dependent| {
+                            serde_json::json!({
+                                "path": relative(&dependent.path),
+                                "direct": dependent.is_direct(),
+                                "chain": dependent
+                                    .chain
+                                    .iter()
+                                    .map(|p| relative(p))
+                                    .collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect();
+                    let json_output = serde_json::to_string_pretty(&serde_json::json!({
+                        "target": relative(&target),
+                        "dependents": entries,
+                    }))?;
                     println!("{}", json_output);
                 }
             }
@@ -324,3 +626,45 @@ pub async fn run(cli: Cli) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_entries_expands_globs_and_drops_duplicates() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/bundle");
+        let entries = resolve_entries(&[
+            root.join("main.ts"),
+            root.join("*.ts"),
+            root.join("./main.ts"),
+        ])
+        .unwrap();
+
+        assert_eq!(entries, vec![root.join("main.ts"), root.join("greet.ts")]);
+        assert!(resolve_entries(&[root.join("*.rs")]).is_err());
+    }
+
+    #[test]
+    fn test_common_parent() {
+        let paths = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            common_parent(&paths(&[
+                "/p/web/src/main.ts",
+                "/p/web/cli.ts",
+                "/p/web/src/a/b.ts"
+            ])),
+            Some(PathBuf::from("/p/web"))
+        );
+        assert_eq!(
+            common_parent(&paths(&["/p/web/main.ts"])),
+            Some(PathBuf::from("/p/web"))
+        );
+        assert_eq!(
+            common_parent(&paths(&["/p/web/main.ts", "/q/main.ts"])),
+            Some(PathBuf::from("/"))
+        );
+        assert_eq!(common_parent(&[]), None);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::core::fs::find_git_root;
+use crate::core::traverser::ImportFilter;
+use crate::output::{Detail, OutputFormat};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-level configuration file
+pub const CONFIG_FILE_NAME: &str = "packlet.toml";
+
+/// Settings loaded from `packlet.toml`.
+///
+/// Every value is optional so that several files can be layered on top of each
+/// other. Precedence is CLI flags > project file > user-level file > defaults;
+/// the CLI applies its own flags and the built-in defaults when reading a value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackletConfig {
+    pub output: OutputConfig,
+    pub javascript: JavaScriptConfig,
+    pub python: PythonConfig,
+    pub go: GoConfig,
+    pub css: CssConfig,
+    pub traversal: TraversalConfig,
+    pub cache: CacheConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub format: Option<OutputFormat>,
+    /// Approximate token budget for bundles
+    pub max_tokens: Option<usize>,
+    /// Add the type declarations of imported external packages
+    pub external_api: Option<bool>,
+    /// Approximate token cap for the external declarations (default: 20000)
+    pub external_api_max_tokens: Option<usize>,
+    /// Leave out the declarations of dependencies that no importer uses
+    pub slice: Option<bool>,
+    /// How much of each dependency to include (default: full)
+    pub detail: Option<Detail>,
+    /// Import distance up to which `detail = "signatures"` keeps files in
+    /// full (default: 0, only the entries)
+    pub full_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JavaScriptConfig {
+    pub resolution: Option<Resolution>,
+    /// Explicit tsconfig/jsconfig, relative to the config file that set it
+    pub tsconfig_path: Option<PathBuf>,
+    /// Packages treated as external in addition to the nearest package.json
+    /// dependencies and Node builtins. `@scope/*` matches a whole scope.
+    pub externals: Option<Vec<String>>,
+    /// Packages never treated as external, even when declared as dependencies
+    pub internals: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PythonConfig {
+    /// Directories absolute imports are resolved against before the detected
+    /// ones, relative to the config file that set them
+    pub source_roots: Option<Vec<PathBuf>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GoConfig {
+    /// Build tags files are selected with, including the target `GOOS` and
+    /// `GOARCH`. Without them every non-test file of a package is included.
+    pub build_tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CssConfig {
+    /// Directories stylesheet imports are looked up in after the importing
+    /// file's own, like Sass `includePaths`. Relative to the config file.
+    pub include_paths: Option<Vec<PathBuf>>,
+}
+
+/// How bare specifiers are resolved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    /// Node resolution plus tsconfig/jsconfig `paths` aliases
+    #[default]
+    Typescript,
+    /// Plain Node resolution, tsconfig/jsconfig files are ignored
+    Node,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraversalConfig {
+    pub max_depth: Option<usize>,
+    pub max_files: Option<usize>,
+    /// Timeout in seconds
+    pub timeout: Option<u64>,
+    pub exclude: Option<Vec<String>>,
+    pub extensions: Option<Vec<String>>,
+    pub strict_extensions: Option<bool>,
+    /// Follow only runtime or only type-only imports
+    pub imports: Option<ImportFilter>,
+    /// Follow the imports of assets an adapter can parse, like stylesheets
+    /// imported from scripts
+    pub include_assets: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Persist parsed imports between runs
+    pub enabled: Option<bool>,
+    /// Cache directory, relative to the config file that set it
+    pub dir: Option<PathBuf>,
+}
+
+impl PackletConfig {
+    /// Parse a single config file, resolving relative paths against its directory
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let mut config: PackletConfig = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+
+        let config_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        if let Some(tsconfig_path) = config.javascript.tsconfig_path.take() {
+            config.javascript.tsconfig_path = Some(config_dir.join(tsconfig_path));
+        }
+        if let Some(roots) = config.python.source_roots.take() {
+            config.python.source_roots =
+                Some(roots.iter().map(|root| config_dir.join(root)).collect());
+        }
+        if let Some(paths) = config.css.include_paths.take() {
+            config.css.include_paths =
+                Some(paths.iter().map(|path| config_dir.join(path)).collect());
+        }
+        if let Some(dir) = config.cache.dir.take() {
+            config.cache.dir = Some(config_dir.join(dir));
+        }
+
+        Ok(config)
+    }
+
+    /// Load the effective configuration for an entry file.
+    ///
+    /// The user-level file is applied first, then either the explicit `--config`
+    /// file or the nearest `packlet.toml` between the entry and the git root.
+    pub async fn load(entry: &Path, explicit: Option<&Path>) -> Result<Self> {
+        let mut config = PackletConfig::default();
+
+        if let Some(user_path) = user_config_path().filter(|p| p.is_file()) {
+            log::debug!("Using user config: {}", user_path.display());
+            config = config.merge(PackletConfig::from_file(&user_path)?);
+        }
+
+        let project_path = match explicit {
+            Some(path) => {
+                if !path.is_file() {
+                    return Err(anyhow::anyhow!("Config file not found: {}", path.display()));
+                }
+                Some(path.to_path_buf())
+            }
+            None => discover_project_config(entry).await,
+        };
+
+        if let Some(project_path) = project_path {
+            log::info!("Using config: {}", project_path.display());
+            config = config.merge(PackletConfig::from_file(&project_path)?);
+        }
+
+        Ok(config)
+    }
+
+    /// Layer `other` on top of `self`, values set in `other` win
+    pub fn merge(self, other: PackletConfig) -> Self {
+        Self {
+            output: OutputConfig {
+                format: other.output.format.or(self.output.format),
+                max_tokens: other.output.max_tokens.or(self.output.max_tokens),
+                external_api: other.output.external_api.or(self.output.external_api),
+                external_api_max_tokens: other
+                    .output
+                    .external_api_max_tokens
+                    .or(self.output.external_api_max_tokens),
+                slice: other.output.slice.or(self.output.slice),
+                detail: other.output.detail.or(self.output.detail),
+                full_depth: other.output.full_depth.or(self.output.full_depth),
+            },
+            javascript: JavaScriptConfig {
+                resolution: other.javascript.resolution.or(self.javascript.resolution),
+                tsconfig_path: other
+                    .javascript
+                    .tsconfig_path
+                    .or(self.javascript.tsconfig_path),
+                externals: other.javascript.externals.or(self.javascript.externals),
+                internals: other.javascript.internals.or(self.javascript.internals),
+            },
+            python: PythonConfig {
+                source_roots: other.python.source_roots.or(self.python.source_roots),
+            },
+            go: GoConfig {
+                build_tags: other.go.build_tags.or(self.go.build_tags),
+            },
+            css: CssConfig {
+                include_paths: other.css.include_paths.or(self.css.include_paths),
+            },
+            traversal: TraversalConfig {
+                max_depth: other.traversal.max_depth.or(self.traversal.max_depth),
+                max_files: other.traversal.max_files.or(self.traversal.max_files),
+                timeout: other.traversal.timeout.or(self.traversal.timeout),
+                exclude: other.traversal.exclude.or(self.traversal.exclude),
+                extensions: other.traversal.extensions.or(self.traversal.extensions),
+                strict_extensions: other
+                    .traversal
+                    .strict_extensions
+                    .or(self.traversal.strict_extensions),
+                imports: other.traversal.imports.or(self.traversal.imports),
+                include_assets: other
+                    .traversal
+                    .include_assets
+                    .or(self.traversal.include_assets),
+            },
+            cache: CacheConfig {
+                enabled: other.cache.enabled.or(self.cache.enabled),
+                dir: other.cache.dir.or(self.cache.dir),
+            },
+        }
+    }
+}
+
+/// Find the nearest `packlet.toml` from the entry file up to the git root
+pub async fn discover_project_config(entry: &Path) -> Option<PathBuf> {
+    let git_root = find_git_root(entry).await;
+    let mut current = if entry.is_dir() {
+        entry.to_path_buf()
+    } else {
+        entry.parent()?.to_path_buf()
+    };
+
+    loop {
+        let candidate = current.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+
+        if git_root.as_deref() == Some(current.as_path()) {
+            return None;
+        }
+
+        current = current.parent()?.to_path_buf();
+    }
+}
+
+/// Location of the user-level config file (`~/.config/packlet/config.toml`)
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("packlet").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_documented_schema() {
+        let config: PackletConfig = toml::from_str(
+            r#"
+            [output]
+            format = "markdown"
+
+            [javascript]
+            resolution = "typescript"
+            tsconfig_path = "./tsconfig.json"
+
+            [traversal]
+            max_depth = 50
+            exclude = ["**/generated/**"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.output.format, Some(OutputFormat::Markdown));
+        assert_eq!(config.javascript.resolution, Some(Resolution::Typescript));
+        assert_eq!(config.traversal.max_depth, Some(50));
+        assert_eq!(
+            config.traversal.exclude,
+            Some(vec!["**/generated/**".to_string()])
+        );
+        assert!(config.traversal.max_files.is_none());
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        let result: Result<PackletConfig, _> = toml::from_str("[traversal]\nmax_dept = 3\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_merge_precedence() {
+        let user: PackletConfig =
+            toml::from_str("[traversal]\nmax_depth = 10\nmax_files = 500\n").unwrap();
+        let project: PackletConfig = toml::from_str("[traversal]\nmax_depth = 3\n").unwrap();
+
+        let merged = user.merge(project);
+        assert_eq!(merged.traversal.max_depth, Some(3));
+        assert_eq!(merged.traversal.max_files, Some(500));
+        assert!(merged.traversal.timeout.is_none());
+    }
+}
@@ -0,0 +1,478 @@
+use crate::adapters::javascript::ALIAS_CONFIG_FILES;
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter, ResolvedImport};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// Default cache location, relative to the project root
+pub const DEFAULT_CACHE_DIR: &str = ".packlet/cache";
+
+const CACHE_FILE_NAME: &str = "imports.json";
+
+/// Files whose contents change how imports resolve, besides the alias
+/// configs in [`ALIAS_CONFIG_FILES`]
+const RESOLUTION_CONFIG_FILES: &[&str] = &[
+    "tsconfig.json",
+    "jsconfig.json",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "pyproject.toml",
+    "Cargo.toml",
+    "go.mod",
+];
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    settings: u64,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    content_hash: u64,
+    /// Hash of the resolution config files that apply to this file
+    config_hash: u64,
+    imports: Vec<ImportStatement>,
+    /// Successful resolutions by [`resolution_key`]
+    resolutions: HashMap<String, Vec<CachedResolution>>,
+    /// Modification time of the file's own directory when the resolutions
+    /// were made. A file added next to it can shadow any of them.
+    #[serde(default)]
+    dir_mtime: Option<u128>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedResolution {
+    path: PathBuf,
+    is_local: bool,
+    is_asset: bool,
+    /// Modification time of the directory holding `path`, which changes when
+    /// files are added to or removed from it
+    dir_mtime: Option<u128>,
+}
+
+/// Parsed imports and resolutions persisted between runs.
+///
+/// Entries are keyed by file path and only reused while the content hash, the
+/// packlet version and the adapter settings match. Resolutions are dropped
+/// when a resolution config file above the importing file changes or files are
+/// added to or removed from its directory, and are re-checked against the
+/// target directory before being reused.
+///
+/// Other directories searched before the match, like include paths or parent
+/// `node_modules`, are not watched: a file added there that would now win is
+/// only picked up once the cache entry is invalidated some other way.
+pub struct ParseCache {
+    path: PathBuf,
+    settings: u64,
+    entries: DashMap<PathBuf, CacheEntry>,
+    config_hashes: DashMap<PathBuf, u64>,
+    dirty: AtomicBool,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl ParseCache {
+    /// Opens the cache stored in `dir`. `settings` identifies the adapter
+    /// configuration; a cache written with different settings starts empty.
+    pub fn open(dir: &Path, settings: &str) -> Self {
+        let path = dir.join(CACHE_FILE_NAME);
+        let settings = hash_of(settings);
+
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<CacheFile>(&content) {
+                Ok(file)
+                    if file.version == env!("CARGO_PKG_VERSION") && file.settings == settings =>
+                {
+                    file.entries.into_iter().collect()
+                }
+                Ok(_) => {
+                    log::info!("Parse cache at {} is outdated, rebuilding", path.display());
+                    DashMap::new()
+                }
+                Err(e) => {
+                    log::warn!("Ignoring corrupt parse cache {}: {}", path.display(), e);
+                    DashMap::new()
+                }
+            },
+            Err(_) => DashMap::new(),
+        };
+
+        Self {
+            path,
+            settings,
+            entries,
+            config_hashes: DashMap::new(),
+            dirty: AtomicBool::new(false),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Writes the cache back to disk if anything changed
+    pub fn save(&self) -> Result<()> {
+        log::info!(
+            "Parse cache: {} hits, {} misses",
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed)
+        );
+
+        if !self.dirty.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let file = CacheFile {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            settings: self.settings,
+            entries: self
+                .entries
+                .iter()
+                .map(|e| (e.key().clone(), e.value().clone()))
+                .collect(),
+        };
+
+        let dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+
+        // Write to a temporary file first so concurrent runs never read a
+        // partially written cache
+        let tmp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec(&file)?)
+            .with_context(|| format!("Failed to write parse cache {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write parse cache {}", self.path.display()))?;
+
+        Ok(())
+    }
+
+    fn imports(&self, file: &Path, content: &str) -> Option<Vec<ImportStatement>> {
+        let entry = self.entries.get(file)?;
+        if entry.content_hash != hash_of(content) {
+            return None;
+        }
+        Some(entry.imports.clone())
+    }
+
+    fn store_imports(&self, file: &Path, content: &str, imports: &[ImportStatement]) {
+        self.entries.insert(
+            file.to_path_buf(),
+            CacheEntry {
+                content_hash: hash_of(content),
+                config_hash: self.config_hash(file.parent().unwrap_or_else(|| Path::new("/"))),
+                imports: imports.to_vec(),
+                resolutions: HashMap::new(),
+                dir_mtime: dir_mtime(file),
+            },
+        );
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    fn resolution(&self, file: &Path, import: &ImportStatement) -> Option<Vec<ResolvedImport>> {
+        let mut entry = self.entries.get_mut(file)?;
+
+        let config_hash = self.config_hash(file.parent().unwrap_or_else(|| Path::new("/")));
+        let file_dir_mtime = dir_mtime(file);
+        if entry.config_hash != config_hash || entry.dir_mtime != file_dir_mtime {
+            entry.config_hash = config_hash;
+            entry.dir_mtime = file_dir_mtime;
+            entry.resolutions.clear();
+            self.dirty.store(true, Ordering::Relaxed);
+            return None;
+        }
+
+        let cached = entry.resolutions.get(&resolution_key(import))?;
+        if cached
+            .iter()
+            .any(|c| !c.path.exists() || dir_mtime(&c.path) != c.dir_mtime)
+        {
+            return None;
+        }
+
+        Some(
+            cached
+                .iter()
+                .map(|c| ResolvedImport {
+                    path: c.path.clone(),
+                    is_local: c.is_local,
+                    is_asset: c.is_asset,
+                })
+                .collect(),
+        )
+    }
+
+    fn store_resolution(&self, file: &Path, import: &ImportStatement, resolved: &[ResolvedImport]) {
+        let Some(mut entry) = self.entries.get_mut(file) else {
+            return;
+        };
+        entry.resolutions.insert(
+            resolution_key(import),
+            resolved
+                .iter()
+                .map(|r| CachedResolution {
+                    path: r.path.clone(),
+                    is_local: r.is_local,
+                    is_asset: r.is_asset,
+                    dir_mtime: dir_mtime(&r.path),
+                })
+                .collect(),
+        );
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Combined hash of the resolution config files in `dir` and its ancestors
+    fn config_hash(&self, dir: &Path) -> u64 {
+        if let Some(hash) = self.config_hashes.get(dir) {
+            return *hash;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        if let Some(parent) = dir.parent() {
+            self.config_hash(parent).hash(&mut hasher);
+        }
+        for name in RESOLUTION_CONFIG_FILES.iter().chain(ALIAS_CONFIG_FILES) {
+            if let Ok(content) = std::fs::read(dir.join(name)) {
+                name.hash(&mut hasher);
+                content.hash(&mut hasher);
+            }
+        }
+
+        let hash = hasher.finish();
+        self.config_hashes.insert(dir.to_path_buf(), hash);
+        hash
+    }
+}
+
+/// Wraps an adapter so parsed imports and resolutions go through a [`ParseCache`]
+pub struct CachedAdapter {
+    inner: Arc<dyn LanguageAdapter>,
+    cache: Arc<ParseCache>,
+}
+
+impl CachedAdapter {
+    pub fn new(inner: Arc<dyn LanguageAdapter>, cache: Arc<ParseCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl LanguageAdapter for CachedAdapter {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        self.inner.supported_extensions()
+    }
+
+    async fn parse_imports(
+        &self,
+        file_path: &Path,
+        content: &str,
+        context: &AnalysisContext,
+    ) -> Result<Vec<ImportStatement>> {
+        if let Some(imports) = self.cache.imports(file_path, content) {
+            self.cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(imports);
+        }
+
+        self.cache.misses.fetch_add(1, Ordering::Relaxed);
+        let imports = self
+            .inner
+            .parse_imports(file_path, content, context)
+            .await?;
+        self.cache.store_imports(file_path, content, &imports);
+        Ok(imports)
+    }
+
+    async fn resolve_import(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Option<ResolvedImport>> {
+        Ok(self
+            .resolve_import_all(import, from_file, context)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    async fn resolve_import_all(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Vec<ResolvedImport>> {
+        if let Some(resolved) = self.cache.resolution(from_file, import) {
+            return Ok(resolved);
+        }
+
+        // Unresolved imports are mostly externals, which are cheap to detect
+        // again and would otherwise go stale when a matching file appears
+        let resolved = self
+            .inner
+            .resolve_import_all(import, from_file, context)
+            .await?;
+        if !resolved.is_empty() {
+            self.cache.store_resolution(from_file, import, &resolved);
+        }
+        Ok(resolved)
+    }
+
+    fn can_parse_file(&self, file_path: &Path) -> bool {
+        self.inner.can_parse_file(file_path)
+    }
+}
+
+/// Key of a cached resolution. Adapters may resolve one specifier to different
+/// files depending on the kind of import and the names it binds, like Python's
+/// `from app import views` and `from app import models`, so the whole import
+/// except its position is part of the key.
+fn resolution_key(import: &ImportStatement) -> String {
+    let key = serde_json::to_string(&(&import.specifier, &import.kind, &import.bindings))
+        .unwrap_or_else(|_| import.specifier.clone());
+    format!("{:016x}", hash_of(&key))
+}
+
+/// `DefaultHasher` uses fixed keys, so hashes are stable for a given build;
+/// the cache is discarded on version changes anyway
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Modification time of the directory holding `path`
+fn dir_mtime(path: &Path) -> Option<u128> {
+    let dir = path.parent()?;
+    let modified = std::fs::metadata(dir).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::python::PythonAdapter;
+    use crate::core::fs::LocalFileSystem;
+    use crate::core::language::ImportKind;
+
+    #[test]
+    fn test_imports_survive_reopen_until_content_changes() {
+        let dir = std::env::temp_dir().join(format!("packlet-cache-{}", std::process::id()));
+        let file = dir.join("index.ts");
+        let imports = vec![ImportStatement {
+            specifier: "./a".to_string(),
+            kind: ImportKind::EsModule,
+            line: 1,
+            column: 0,
+            raw: "import './a'".to_string(),
+            bindings: Vec::new(),
+        }];
+
+        let cache = ParseCache::open(&dir, "settings");
+        cache.store_imports(&file, "import './a'", &imports);
+        cache.save().unwrap();
+
+        let reopened = ParseCache::open(&dir, "settings");
+        let cached = reopened.imports(&file, "import './a'").unwrap();
+        assert_eq!(cached[0].specifier, "./a");
+        assert!(reopened.imports(&file, "import './b'").is_none());
+        assert!(ParseCache::open(&dir, "other settings")
+            .imports(&file, "import './a'")
+            .is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolutions_dropped_when_importing_directory_changes() {
+        let dir = std::env::temp_dir().join(format!("packlet-resolve-{}", std::process::id()));
+        let file = dir.join("src/index.ts");
+        let target = dir.join("lib/util.ts");
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::write(&file, "import 'util'").unwrap();
+        std::fs::write(&target, "").unwrap();
+        let resolved = [ResolvedImport {
+            path: target.clone(),
+            is_local: true,
+            is_asset: false,
+        }];
+
+        let import = ImportStatement {
+            specifier: "util".to_string(),
+            kind: ImportKind::EsModule,
+            line: 1,
+            column: 0,
+            raw: "import 'util'".to_string(),
+            bindings: Vec::new(),
+        };
+
+        let cache = ParseCache::open(&dir.join("cache"), "settings");
+        cache.store_imports(&file, "import 'util'", &[]);
+        cache.store_resolution(&file, &import, &resolved);
+        assert_eq!(cache.resolution(&file, &import).unwrap()[0].path, target);
+
+        // A file next to the importer may now shadow the cached target
+        std::fs::write(dir.join("src/util.ts"), "").unwrap();
+        assert!(cache.resolution(&file, &import).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolutions_keyed_by_whole_import() {
+        // Cached targets are checked on disk, so the project is real
+        let dir = std::env::temp_dir().join(format!("packlet-key-{}", std::process::id()));
+        let main = dir.join("main.py");
+        let content = "from app import views\nfrom app import models\n";
+        std::fs::create_dir_all(dir.join("app")).unwrap();
+        std::fs::write(&main, content).unwrap();
+        for name in ["__init__.py", "views.py", "models.py"] {
+            std::fs::write(dir.join("app").join(name), "").unwrap();
+        }
+        let context = AnalysisContext {
+            fs: Arc::new(LocalFileSystem),
+        };
+        let adapter = CachedAdapter::new(
+            Arc::new(PythonAdapter::new()),
+            Arc::new(ParseCache::open(&dir.join("cache"), "settings")),
+        );
+
+        let imports = adapter
+            .parse_imports(&main, content, &context)
+            .await
+            .unwrap();
+        assert_eq!(imports[0].specifier, imports[1].specifier);
+
+        // Twice, so the second round is served from the cache
+        for _ in 0..2 {
+            let mut targets = Vec::new();
+            for import in &imports {
+                for resolved in adapter
+                    .resolve_import_all(import, &main, &context)
+                    .await
+                    .unwrap()
+                {
+                    targets.push(resolved.path);
+                }
+            }
+            assert!(targets.contains(&dir.join("app/views.py")), "{targets:?}");
+            assert!(targets.contains(&dir.join("app/models.py")), "{targets:?}");
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
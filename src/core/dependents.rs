@@ -0,0 +1,263 @@
+use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter};
+use crate::core::traverser::should_exclude_path;
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use glob::Pattern;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use walkdir::WalkDir;
+
+/// Import graph of every parseable file under a project root
+#[derive(Debug, Default)]
+pub struct ProjectGraph {
+    pub root: PathBuf,
+    pub adj_list: HashMap<PathBuf, Vec<(PathBuf, ImportStatement)>>,
+    pub files_scanned: usize,
+}
+
+/// A file that transitively imports the target
+#[derive(Debug, Clone)]
+pub struct Dependent {
+    pub path: PathBuf,
+    /// Shortest import chain, starting at `path` and ending at the target
+    pub chain: Vec<PathBuf>,
+}
+
+impl Dependent {
+    pub fn is_direct(&self) -> bool {
+        self.chain.len() == 2
+    }
+}
+
+impl ProjectGraph {
+    /// Parses every file under `root` that one of the adapters understands,
+    /// using the first such adapter, and resolves its local imports. Paths
+    /// matching `exclude_patterns` or the default excludes are skipped.
+    pub async fn scan(
+        root: &Path,
+        adapters: Vec<Arc<dyn LanguageAdapter>>,
+        context: Arc<AnalysisContext>,
+        exclude_patterns: &[String],
+    ) -> Result<Self> {
+        let patterns: Vec<Pattern> = exclude_patterns
+            .iter()
+            .filter_map(|p| {
+                Pattern::new(p).ok().or_else(|| {
+                    log::warn!("Invalid exclude pattern: {}", p);
+                    None
+                })
+            })
+            .collect();
+
+        let root = context.fs.canonicalize(root).await?;
+        let files: Vec<(PathBuf, Arc<dyn LanguageAdapter>)> = WalkDir::new(&root)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| !should_exclude_path(e.path(), &patterns))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let adapter = adapters.iter().find(|a| a.can_parse_file(e.path()))?;
+                Some((e.into_path(), adapter.clone()))
+            })
+            .collect();
+
+        log::info!("Scanning {} files under {}", files.len(), root.display());
+
+        let results: Vec<(PathBuf, Vec<(PathBuf, ImportStatement)>)> = stream::iter(files)
+            .map(|(file, adapter)| {
+                let context = context.clone();
+                async move {
+                    let edges = scan_file(&file, &*adapter, &context).await;
+                    (file, edges)
+                }
+            })
+            .buffer_unordered(num_cpus::get().max(1) * 4)
+            .collect()
+            .await;
+
+        let files_scanned = results.len();
+        let adj_list = results
+            .into_iter()
+            .filter(|(_, edges)| !edges.is_empty())
+            .collect();
+
+        Ok(Self {
+            root,
+            adj_list,
+            files_scanned,
+        })
+    }
+
+    /// Every file that transitively imports `target`, closest importers first
+    pub fn dependents_of(&self, target: &Path) -> Vec<Dependent> {
+        let mut reverse: HashMap<&Path, Vec<&Path>> = HashMap::new();
+        for (from, deps) in &self.adj_list {
+            for (to, _) in deps {
+                reverse
+                    .entry(to.as_path())
+                    .or_default()
+                    .push(from.as_path());
+            }
+        }
+
+        // BFS backwards from the target; `next_hop` points one step closer to it
+        let mut next_hop: HashMap<&Path, &Path> = HashMap::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([target]);
+
+        while let Some(current) = queue.pop_front() {
+            let Some(importers) = reverse.get(current) else {
+                continue;
+            };
+            let mut importers = importers.clone();
+            importers.sort();
+            for importer in importers {
+                if importer == target || next_hop.contains_key(importer) {
+                    continue;
+                }
+                next_hop.insert(importer, current);
+                order.push(importer);
+                queue.push_back(importer);
+            }
+        }
+
+        order
+            .into_iter()
+            .map(|path| {
+                let mut chain = vec![path.to_path_buf()];
+                let mut current = path;
+                while let Some(&next) = next_hop.get(current) {
+                    chain.push(next.to_path_buf());
+                    current = next;
+                }
+                if current != target {
+                    chain.push(target.to_path_buf());
+                }
+                Dependent {
+                    path: path.to_path_buf(),
+                    chain,
+                }
+            })
+            .collect()
+    }
+}
+
+async fn scan_file(
+    file: &Path,
+    adapter: &dyn LanguageAdapter,
+    context: &AnalysisContext,
+) -> Vec<(PathBuf, ImportStatement)> {
+    let content = match context.fs.read_file(file).await {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("Could not read file {}: {}", file.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let imports = match adapter.parse_imports(file, &content, context).await {
+        Ok(imports) => imports,
+        Err(e) => {
+            log::warn!("Could not parse file {}: {}", file.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut edges = Vec::new();
+    for import in imports {
+        match adapter.resolve_import_all(&import, file, context).await {
+            Ok(resolved) => {
+                for resolved in resolved.into_iter().filter(|r| r.is_local) {
+                    let path = context
+                        .fs
+                        .canonicalize(&resolved.path)
+                        .await
+                        .unwrap_or(resolved.path);
+                    edges.push((path, import.clone()));
+                }
+            }
+            Err(e) => log::debug!(
+                "Could not resolve '{}' from {}: {}",
+                import.specifier,
+                file.display(),
+                e
+            ),
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language::ImportKind;
+
+    fn graph(edges: &[(&str, &str)]) -> ProjectGraph {
+        let mut adj_list: HashMap<PathBuf, Vec<(PathBuf, ImportStatement)>> = HashMap::new();
+        for (from, to) in edges {
+            let import = ImportStatement {
+                specifier: to.to_string(),
+                kind: ImportKind::EsModule,
+                line: 1,
+                column: 0,
+                raw: String::new(),
+                bindings: Vec::new(),
+            };
+            adj_list
+                .entry(PathBuf::from(from))
+                .or_default()
+                .push((PathBuf::from(to), import));
+        }
+        ProjectGraph {
+            root: PathBuf::from("/p"),
+            adj_list,
+            files_scanned: edges.len(),
+        }
+    }
+
+    #[test]
+    fn test_dependents_of_follows_shortest_chains() {
+        let project = graph(&[
+            ("/p/c.ts", "/p/target.ts"),
+            ("/p/b.ts", "/p/target.ts"),
+            ("/p/a.ts", "/p/b.ts"),
+            ("/p/a.ts", "/p/c.ts"),
+            ("/p/d.ts", "/p/a.ts"),
+            // Cycles back through the target and unrelated files are ignored
+            ("/p/target.ts", "/p/a.ts"),
+            ("/p/b.ts", "/p/a.ts"),
+            ("/p/e.ts", "/p/f.ts"),
+        ]);
+
+        let dependents: Vec<(String, bool, Vec<String>)> = project
+            .dependents_of(Path::new("/p/target.ts"))
+            .into_iter()
+            .map(|d| {
+                let chain = d.chain.iter().map(|p| p.display().to_string()).collect();
+                (d.path.display().to_string(), d.is_direct(), chain)
+            })
+            .collect();
+
+        let chain = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            dependents,
+            vec![
+                ("/p/b.ts".into(), true, chain(&["/p/b.ts", "/p/target.ts"])),
+                ("/p/c.ts".into(), true, chain(&["/p/c.ts", "/p/target.ts"])),
+                (
+                    "/p/a.ts".into(),
+                    false,
+                    chain(&["/p/a.ts", "/p/b.ts", "/p/target.ts"])
+                ),
+                (
+                    "/p/d.ts".into(),
+                    false,
+                    chain(&["/p/d.ts", "/p/a.ts", "/p/b.ts", "/p/target.ts"])
+                ),
+            ]
+        );
+        assert!(project.dependents_of(Path::new("/p/d.ts")).is_empty());
+    }
+}
@@ -31,6 +31,12 @@ pub trait FileSystemProvider: Send + Sync {
     async fn exists(&self, path: &Path) -> bool;
     async fn is_directory(&self, path: &Path) -> bool;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
+    /// Entries of a directory, sorted by path. Providers that can't list
+    /// directories may keep the default; workspace globs and Go packages then
+    /// only find what they can guess from file names.
+    async fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        anyhow::bail!("Cannot list {}: read_dir not supported", path.display())
+    }
 }
 
 pub struct LocalFileSystem;
@@ -56,6 +62,16 @@ impl FileSystemProvider for LocalFileSystem {
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(tokio::fs::canonicalize(path).await?)
     }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let mut entries = Vec::new();
+        let mut dir = fs::read_dir(path).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            entries.push(entry.path());
+        }
+        entries.sort();
+        Ok(entries)
+    }
 }
 
 pub struct CachedFileSystem {
@@ -110,4 +126,80 @@ impl FileSystemProvider for CachedFileSystem {
 
         Ok(canonical)
     }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        self.inner.read_dir(path).await
+    }
+}
+
+/// Files held in memory, for tests of resolution logic. Directories exist
+/// implicitly above every file.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryFileSystem {
+    files: std::collections::HashMap<PathBuf, String>,
+}
+
+#[cfg(test)]
+impl MemoryFileSystem {
+    pub fn new<'a>(files: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+                .collect(),
+        }
+    }
+
+    fn normalize(path: &Path) -> PathBuf {
+        use path_absolutize::Absolutize;
+        path.absolutize()
+            .map_or_else(|_| path.to_path_buf(), |p| p.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl FileSystemProvider for MemoryFileSystem {
+    async fn read_file(&self, path: &Path) -> Result<String> {
+        self.files
+            .get(&Self::normalize(path))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No such file: {}", path.display()))
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        let path = Self::normalize(path);
+        self.files.contains_key(&path) || self.is_directory(&path).await
+    }
+
+    async fn is_directory(&self, path: &Path) -> bool {
+        let path = Self::normalize(path);
+        self.files
+            .keys()
+            .any(|file| file != &path && file.starts_with(&path))
+    }
+
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        if self.exists(path).await {
+            Ok(Self::normalize(path))
+        } else {
+            Err(anyhow::anyhow!("No such file: {}", path.display()))
+        }
+    }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let path = Self::normalize(path);
+        let mut entries: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter_map(|file| {
+                let child = file.strip_prefix(&path).ok()?.components().next()?;
+                Some(path.join(child))
+            })
+            .collect();
+        entries.sort();
+        entries.dedup();
+        Ok(entries)
+    }
 }
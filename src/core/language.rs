@@ -1,4 +1,10 @@
+use crate::adapters::css::CssAdapter;
+use crate::adapters::go::GoAdapter;
+use crate::adapters::html::HtmlAdapter;
 use crate::adapters::javascript::JsAdapter;
+use crate::adapters::python::PythonAdapter;
+use crate::adapters::rust::RustAdapter;
+use crate::config::PackletConfig;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
@@ -25,6 +31,21 @@ pub trait LanguageAdapter: Send + Sync {
         context: &AnalysisContext,
     ) -> Result<Option<ResolvedImport>>;
 
+    /// Every file an import stands for, such as all files of a Go package.
+    /// Defaults to the single file from [`LanguageAdapter::resolve_import`].
+    async fn resolve_import_all(
+        &self,
+        import: &ImportStatement,
+        from_file: &Path,
+        context: &AnalysisContext,
+    ) -> Result<Vec<ResolvedImport>> {
+        Ok(self
+            .resolve_import(import, from_file, context)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
     fn can_parse_file(&self, file_path: &Path) -> bool {
         if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
             self.supported_extensions().contains(&ext)
@@ -34,21 +55,53 @@ pub trait LanguageAdapter: Send + Sync {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ImportStatement {
     pub specifier: String,
     pub kind: ImportKind,
     pub line: usize,
     pub column: usize,
     pub raw: String,
+    /// Names the statement imports or re-exports; empty for side-effect and
+    /// dynamic imports
+    #[serde(default)]
+    pub bindings: Vec<ImportBinding>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// A name brought in by an import statement, or passed on by a re-export
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "binding", rename_all = "snake_case")]
+pub enum ImportBinding {
+    /// `import local from`
+    Default { local: String, type_only: bool },
+    /// `import { imported as local } from`
+    Named {
+        imported: String,
+        local: String,
+        type_only: bool,
+    },
+    /// `import * as local from`, or a whole CommonJS module
+    Namespace { local: String, type_only: bool },
+    /// `export { imported as exported } from`
+    ReExport {
+        imported: String,
+        exported: String,
+        type_only: bool,
+    },
+    /// `export * as exported from`
+    ReExportNamespace { exported: String },
+    /// `export * from`
+    ReExportAll,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum ImportKind {
     EsModule,
     CommonJs,
     Dynamic,
     TypeOnly,
+    /// `export { x } from` and `export * from`, as used by barrel files
+    ReExport,
     Asset,
     Custom(String),
 }
@@ -66,19 +119,33 @@ impl ResolvedImport {
     }
 }
 
-pub fn get_adapter_for_extension(extension: &str) -> Option<Box<dyn LanguageAdapter>> {
-    let js_adapter = JsAdapter::new();
-    if js_adapter.supported_extensions().contains(&extension) {
-        Some(Box::new(js_adapter))
-    } else {
-        None
-    }
+/// Every built-in adapter, configured from `config`, in the order they are
+/// tried for a file
+pub fn get_adapters(config: &PackletConfig) -> Vec<Box<dyn LanguageAdapter>> {
+    vec![
+        Box::new(JsAdapter::new().with_config(&config.javascript)),
+        Box::new(PythonAdapter::new().with_config(&config.python)),
+        Box::new(RustAdapter::new()),
+        Box::new(GoAdapter::new().with_config(&config.go)),
+        Box::new(CssAdapter::new().with_config(&config.css)),
+        Box::new(HtmlAdapter::new()),
+    ]
+}
+
+pub fn get_adapter_for_extension(
+    extension: &str,
+    config: &PackletConfig,
+) -> Option<Box<dyn LanguageAdapter>> {
+    get_adapters(config)
+        .into_iter()
+        .find(|adapter| adapter.supported_extensions().contains(&extension))
 }
 
 pub fn is_parseable_extension(extension: &str) -> bool {
-    // Only JavaScript/TypeScript files should be parsed for imports
+    // Only files with a language adapter should be parsed for imports
     const PARSEABLE_EXTENSIONS: &[&str] = &[
-        "js", "mjs", "cjs", "ts", "tsx", "jsx", "d.ts", "vue", "svelte",
+        "js", "mjs", "cjs", "ts", "tsx", "jsx", "d.ts", "vue", "svelte", "py", "pyi", "rs", "go",
+        "css", "scss", "sass", "less", "html", "htm",
     ];
     PARSEABLE_EXTENSIONS.contains(&extension)
 }
@@ -1,3 +1,6 @@
+pub mod cache;
+pub mod dependents;
 pub mod fs;
 pub mod language;
 pub mod traverser;
+pub mod usage;
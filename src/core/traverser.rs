@@ -1,9 +1,11 @@
-use crate::core::language::{AnalysisContext, ImportStatement, LanguageAdapter};
+use crate::core::language::{
+    AnalysisContext, ImportKind, ImportStatement, LanguageAdapter, ResolvedImport,
+};
 use anyhow::Result;
 use dashmap::DashSet;
 use futures::future::try_join_all;
 use glob::Pattern;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -12,10 +14,15 @@ use tokio::sync::{Mutex, Semaphore};
 
 #[derive(Debug)]
 pub struct DependencyGraph {
+    /// The first entry point, kept for single-entry consumers
     pub entry_point: PathBuf,
+    /// All entry points in the order they were given
+    pub entry_points: Vec<PathBuf>,
     pub adj_list: HashMap<PathBuf, Vec<(PathBuf, ImportStatement)>>,
     pub circular_deps: DashSet<PathBuf>,
     pub assets: DashSet<PathBuf>,
+    /// Imports that did not resolve to a local file, by importing file
+    pub external_imports: HashMap<PathBuf, Vec<ImportStatement>>,
 }
 
 impl serde::Serialize for DependencyGraph {
@@ -24,8 +31,9 @@ impl serde::Serialize for DependencyGraph {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("DependencyGraph", 4)?;
+        let mut state = serializer.serialize_struct("DependencyGraph", 7)?;
         state.serialize_field("entry_point", &self.entry_point)?;
+        state.serialize_field("entry_points", &self.entry_points)?;
         state.serialize_field("adj_list", &self.adj_list)?;
 
         let circular_deps: Vec<PathBuf> = self.circular_deps.iter().map(|p| p.clone()).collect();
@@ -33,17 +41,26 @@ impl serde::Serialize for DependencyGraph {
 
         state.serialize_field("circular_deps", &circular_deps)?;
         state.serialize_field("assets", &assets)?;
+        state.serialize_field("external_imports", &self.external_imports)?;
+        state.serialize_field("reachable_from", &self.reachability())?;
         state.end()
     }
 }
 
 impl DependencyGraph {
     pub fn new(entry_point: PathBuf) -> Self {
+        Self::with_entries(vec![entry_point])
+    }
+
+    /// Creates a graph for several entry points; the first one becomes `entry_point`
+    pub fn with_entries(entry_points: Vec<PathBuf>) -> Self {
         Self {
-            entry_point,
+            entry_point: entry_points.first().cloned().unwrap_or_default(),
+            entry_points,
             adj_list: HashMap::new(),
             circular_deps: DashSet::new(),
             assets: DashSet::new(),
+            external_imports: HashMap::new(),
         }
     }
 
@@ -54,13 +71,158 @@ impl DependencyGraph {
             .push((to.to_path_buf(), import));
     }
 
+    pub fn add_external(&mut self, from: &Path, import: ImportStatement) {
+        self.external_imports
+            .entry(from.to_path_buf())
+            .or_default()
+            .push(import);
+    }
+
     pub fn mark_circular(&self, path: &Path) {
         self.circular_deps.insert(path.to_path_buf());
     }
 
+    /// Marks every file that is part of an import cycle, found as the
+    /// strongly connected components of the graph (Tarjan's algorithm)
+    pub fn mark_cycles(&self) {
+        let no_deps = Vec::new();
+        let deps = |path: &Path| self.adj_list.get(path).unwrap_or(&no_deps);
+
+        let mut index: HashMap<&Path, usize> = HashMap::new();
+        let mut lowlink: HashMap<&Path, usize> = HashMap::new();
+        let mut stack: Vec<&Path> = Vec::new();
+        let mut on_stack: HashSet<&Path> = HashSet::new();
+
+        for start in self.adj_list.keys() {
+            if index.contains_key(start.as_path()) {
+                continue;
+            }
+
+            // Depth-first search with an explicit stack of (file, next dependency)
+            let mut work = vec![(start.as_path(), 0)];
+            index.insert(start, index.len());
+            lowlink.insert(start, index[start.as_path()]);
+            stack.push(start);
+            on_stack.insert(start);
+
+            while let Some(&(node, next)) = work.last() {
+                if let Some((dep, _)) = deps(node).get(next) {
+                    work.last_mut().expect("work is not empty").1 += 1;
+                    let dep = dep.as_path();
+                    if !index.contains_key(dep) {
+                        let i = index.len();
+                        index.insert(dep, i);
+                        lowlink.insert(dep, i);
+                        stack.push(dep);
+                        on_stack.insert(dep);
+                        work.push((dep, 0));
+                    } else if on_stack.contains(dep) {
+                        let low = lowlink[node].min(index[dep]);
+                        lowlink.insert(node, low);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    let low = lowlink[parent].min(lowlink[node]);
+                    lowlink.insert(parent, low);
+                }
+                if lowlink[node] != index[node] {
+                    continue;
+                }
+
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                let self_import = deps(node).iter().any(|(dep, _)| dep == node);
+                if component.len() > 1 || self_import {
+                    for member in component {
+                        self.mark_circular(member);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn add_asset(&self, path: &Path) {
         self.assets.insert(path.to_path_buf());
     }
+
+    pub fn is_entry(&self, path: &Path) -> bool {
+        self.entry_points.iter().any(|e| e == path)
+    }
+
+    /// All files reachable from `entry`, including the entry itself
+    pub fn reachable_from(&self, entry: &Path) -> HashSet<PathBuf> {
+        let mut reachable = HashSet::from([entry.to_path_buf()]);
+        let mut queue = VecDeque::from([entry.to_path_buf()]);
+
+        while let Some(path) = queue.pop_front() {
+            if let Some(deps) = self.adj_list.get(&path) {
+                for (dep, _) in deps {
+                    if reachable.insert(dep.clone()) {
+                        queue.push_back(dep.clone());
+                    }
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Maps every file to the entry points it is reachable from, in entry order
+    pub fn reachability(&self) -> HashMap<PathBuf, Vec<PathBuf>> {
+        let mut reachability: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        for entry in &self.entry_points {
+            for path in self.reachable_from(entry) {
+                reachability.entry(path).or_default().push(entry.clone());
+            }
+        }
+        reachability
+    }
+}
+
+/// Which imports a traversal follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFilter {
+    #[default]
+    All,
+    /// Everything except type-only imports, which are erased at runtime
+    Runtime,
+    /// Only type-only imports, and the re-exports and declaration file imports
+    /// the types are reached through
+    Types,
+}
+
+impl ImportFilter {
+    /// Whether an import of `kind` in `file`, `depth` imports away from an
+    /// entry, is followed. Under `Types`, every file past the entry was reached
+    /// through a type-only import, so its re-exports only carry types too, and
+    /// every import of a declaration file is type-only.
+    pub fn allows(&self, kind: &ImportKind, file: &Path, depth: usize) -> bool {
+        match self {
+            ImportFilter::All => true,
+            ImportFilter::Runtime => !matches!(kind, ImportKind::TypeOnly),
+            ImportFilter::Types => {
+                matches!(kind, ImportKind::TypeOnly)
+                    || (matches!(kind, ImportKind::ReExport) && depth > 0)
+                    || is_declaration_file(file)
+            }
+        }
+    }
+}
+
+fn is_declaration_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".d.ts") || n.ends_with(".d.mts") || n.ends_with(".d.cts"))
 }
 
 /// PathScore evaluates whether a path should be skipped during traversal
@@ -73,8 +235,8 @@ struct PathScore {
 }
 
 impl PathScore {
-    /// Analyzes a path relative to the entry point
-    fn from_path(path: &Path, entry: &Path) -> Self {
+    /// Analyzes a path relative to the closest entry point
+    fn from_path(path: &Path, entries: &[PathBuf]) -> Self {
         let mut score = PathScore::default();
 
         for component in path.components() {
@@ -90,11 +252,12 @@ impl PathScore {
             }
         }
 
-        if let Some(entry_parent) = entry.parent() {
-            if let Ok(relative) = path.strip_prefix(entry_parent) {
-                score.depth = relative.components().count();
-            }
-        }
+        score.depth = entries
+            .iter()
+            .filter_map(|entry| path.strip_prefix(entry.parent()?).ok())
+            .map(|relative| relative.components().count())
+            .min()
+            .unwrap_or(0);
 
         score
     }
@@ -138,7 +301,7 @@ const DEFAULT_EXCLUDES: &[&str] = &[
 ];
 
 /// Checks if a path should be excluded based on patterns
-fn should_exclude_path(path: &Path, exclude_patterns: &[Pattern]) -> bool {
+pub(crate) fn should_exclude_path(path: &Path, exclude_patterns: &[Pattern]) -> bool {
     let path_str = path.to_string_lossy();
 
     for pattern in exclude_patterns {
@@ -175,6 +338,28 @@ fn should_exclude_path(path: &Path, exclude_patterns: &[Pattern]) -> bool {
     })
 }
 
+/// Checks whether a file name ends with one of the given extensions.
+///
+/// Extensions are compared case-insensitively and may be compound (`d.ts`).
+/// An empty list matches every file.
+pub fn matches_extensions(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+
+    let file_name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name.to_ascii_lowercase(),
+        None => return false,
+    };
+
+    extensions.iter().any(|ext| {
+        let ext = ext.trim_start_matches('.').to_ascii_lowercase();
+        file_name
+            .strip_suffix(ext.as_str())
+            .is_some_and(|stem| stem.ends_with('.'))
+    })
+}
+
 /// TraversalStats monitors traversal health and detects stuck scenarios
 #[derive(Clone)]
 struct TraversalStats {
@@ -259,31 +444,46 @@ impl CircuitBreaker {
     }
 }
 
+/// Callback invoked for every parsed file
+pub type FileProgress = Arc<dyn Fn(&Path) + Send + Sync>;
+
 #[derive(Clone)]
 pub struct DependencyTraverser {
     visited: Arc<DashSet<PathBuf>>,
-    in_progress: Arc<DashSet<PathBuf>>,
     max_depth: usize,
     max_files: usize,
     file_count: Arc<AtomicUsize>,
     semaphore: Arc<Semaphore>,
     include_assets: bool,
+    adapters: Arc<Vec<Arc<dyn LanguageAdapter>>>,
     exclude_patterns: Arc<Vec<Pattern>>,
+    extensions: Arc<Vec<String>>,
+    import_filter: ImportFilter,
+    progress: Option<FileProgress>,
     stats: TraversalStats,
     circuit_breaker: CircuitBreaker,
 }
 
+impl Default for DependencyTraverser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DependencyTraverser {
     pub fn new() -> Self {
         Self {
             visited: Arc::new(DashSet::new()),
-            in_progress: Arc::new(DashSet::new()),
             max_depth: 50,
             max_files: 10_000,
             file_count: Arc::new(AtomicUsize::new(0)),
             semaphore: Arc::new(Semaphore::new(32)),
             include_assets: false,
+            adapters: Arc::new(Vec::new()),
             exclude_patterns: Arc::new(Vec::new()),
+            extensions: Arc::new(Vec::new()),
+            import_filter: ImportFilter::All,
+            progress: None,
             stats: TraversalStats::new(30),
             circuit_breaker: CircuitBreaker::new(1000, 50),
         }
@@ -304,11 +504,37 @@ impl DependencyTraverser {
         self
     }
 
+    /// Follow the imports of assets that one of the adapters can parse.
+    /// Otherwise assets end the traversal.
     pub fn with_assets(mut self, include: bool) -> Self {
         self.include_assets = include;
         self
     }
 
+    /// Adapters for files the traversal's own adapter can't parse, picked by
+    /// extension, so a page's scripts and stylesheets are parsed by their
+    /// own adapters.
+    pub fn with_adapters(mut self, adapters: Vec<Arc<dyn LanguageAdapter>>) -> Self {
+        self.adapters = Arc::new(adapters);
+        self
+    }
+
+    /// The adapter that parses `file`: the traversal's own one if it can,
+    /// otherwise the first of the additional adapters that can
+    fn adapter_for(
+        &self,
+        file: &Path,
+        adapter: &Arc<dyn LanguageAdapter>,
+    ) -> Option<Arc<dyn LanguageAdapter>> {
+        if adapter.can_parse_file(file) {
+            return Some(adapter.clone());
+        }
+        self.adapters
+            .iter()
+            .find(|a| a.can_parse_file(file))
+            .cloned()
+    }
+
     pub fn with_exclude_patterns(mut self, patterns: Vec<String>) -> Self {
         let compiled_patterns: Vec<Pattern> = patterns
             .iter()
@@ -323,16 +549,66 @@ impl DependencyTraverser {
         self
     }
 
+    /// Only follow imports into files with these extensions. Files with other
+    /// extensions are left out of the graph entirely, along with everything
+    /// reachable only through them. The entry file is always traversed.
+    pub fn with_extension_filter(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = Arc::new(extensions);
+        self
+    }
+
+    /// Only follow imports of the kinds `filter` allows
+    pub fn with_import_filter(mut self, filter: ImportFilter) -> Self {
+        self.import_filter = filter;
+        self
+    }
+
+    /// Called with every file after its imports have been parsed
+    pub fn with_progress(mut self, progress: FileProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     pub async fn traverse(
         &self,
         entry: &Path,
         adapter: Arc<dyn LanguageAdapter>,
         context: Arc<AnalysisContext>,
     ) -> Result<DependencyGraph> {
-        let graph = Arc::new(Mutex::new(DependencyGraph::new(entry.to_path_buf())));
+        self.traverse_many(&[entry.to_path_buf()], adapter, context)
+            .await
+    }
 
-        self.traverse_recursive(entry.to_path_buf(), adapter, context, graph.clone(), 0)
-            .await?;
+    /// Traverses from several entry points into a single graph. Files shared
+    /// between entries are only visited once.
+    pub async fn traverse_many(
+        &self,
+        entries: &[PathBuf],
+        adapter: Arc<dyn LanguageAdapter>,
+        context: Arc<AnalysisContext>,
+    ) -> Result<DependencyGraph> {
+        let mut entry_points = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let canonical = context
+                .fs
+                .canonicalize(entry)
+                .await
+                .unwrap_or_else(|_| entry.clone());
+            if !entry_points.contains(&canonical) {
+                entry_points.push(canonical);
+            }
+        }
+
+        let graph = Arc::new(Mutex::new(DependencyGraph::with_entries(
+            entry_points.clone(),
+        )));
+
+        // Entries run one after another; files an earlier entry reached are
+        // not parsed again
+        for entry in entry_points {
+            self.traverse_recursive(entry, adapter.clone(), context.clone(), graph.clone(), 0)
+                .await?;
+        }
 
         let error_count = self.circuit_breaker.get_error_count();
         if error_count > 0 {
@@ -342,11 +618,15 @@ impl DependencyTraverser {
             );
         }
 
-        Ok(Arc::try_unwrap(graph)
+        let graph = Arc::try_unwrap(graph)
             .map_err(|_| {
                 anyhow::anyhow!("Failed to unwrap Arc, graph is still referenced elsewhere")
             })?
-            .into_inner())
+            .into_inner();
+        // Dependencies are traversed concurrently, so cycles are only known
+        // once the whole graph is
+        graph.mark_cycles();
+        Ok(graph)
     }
 
     async fn traverse_recursive(
@@ -362,16 +642,23 @@ impl DependencyTraverser {
             return Ok(());
         }
 
-        if should_exclude_path(&file, &self.exclude_patterns) {
+        // Exclusions apply to the real location, so symlinked workspace
+        // packages under node_modules are followed into their source
+        let canonical = context
+            .fs
+            .canonicalize(&file)
+            .await
+            .unwrap_or_else(|_| file.clone());
+        if should_exclude_path(&canonical, &self.exclude_patterns) {
             log::debug!("Skipping excluded path: {}", file.display());
             return Ok(());
         }
 
-        let entry_point = {
+        let entry_points = {
             let g = graph.lock().await;
-            g.entry_point.clone()
+            g.entry_points.clone()
         };
-        let path_score = PathScore::from_path(&file, &entry_point);
+        let path_score = PathScore::from_path(&canonical, &entry_points);
         if path_score.should_skip() {
             log::debug!(
                 "Skipping {} - PathScore: depth={}, node_modules={}, parent_traversals={}, components={}",
@@ -398,37 +685,29 @@ impl DependencyTraverser {
             ));
         }
 
-        if current_count > 0 && current_count % 1000 == 0 {
+        if current_count > 0 && current_count.is_multiple_of(1000) {
             log::info!("Progress: {} files processed...", current_count);
         }
 
-        if current_count > 0 && current_count % 100 == 0 {
+        if current_count > 0 && current_count.is_multiple_of(100) {
             self.stats.check_health().await?;
         }
 
         let _permit = self.semaphore.acquire().await?;
 
-        let canonical = context.fs.canonicalize(&file).await?;
         if self.visited.contains(&canonical) {
             return Ok(());
         }
 
-        if self.in_progress.contains(&canonical) {
-            graph.lock().await.mark_circular(&canonical);
-            return Ok(());
-        }
-
         self.visited.insert(canonical.clone());
-        self.in_progress.insert(canonical.clone());
 
-        if !adapter.can_parse_file(&canonical) {
+        let Some(file_adapter) = self.adapter_for(&canonical, &adapter) else {
             log::debug!(
-                "Skipping non-parseable file: {} (not a JS/TS file)",
+                "Skipping non-parseable file: {} (no adapter for it)",
                 canonical.display()
             );
-            self.in_progress.remove(&canonical);
             return Ok(());
-        }
+        };
 
         let content = match context.fs.read_file(&canonical).await {
             Ok(content) => {
@@ -438,12 +717,14 @@ impl DependencyTraverser {
             Err(e) => {
                 log::warn!("Could not read file {}: {}", canonical.display(), e);
                 self.circuit_breaker.record_error()?;
-                self.in_progress.remove(&canonical);
                 return Ok(());
             }
         };
 
-        let imports = match adapter.parse_imports(&canonical, &content, &context).await {
+        let imports = match file_adapter
+            .parse_imports(&canonical, &content, &context)
+            .await
+        {
             Ok(imports) => {
                 self.circuit_breaker.record_success();
                 imports
@@ -451,44 +732,66 @@ impl DependencyTraverser {
             Err(e) => {
                 log::warn!("Could not parse file {}: {}", canonical.display(), e);
                 self.circuit_breaker.record_error()?;
-                self.in_progress.remove(&canonical);
                 return Ok(());
             }
         };
 
         // Record progress after successfully parsing a file
         self.stats.record_progress().await;
+        if let Some(ref progress) = self.progress {
+            progress(&canonical);
+        }
 
         let mut tasks = Vec::new();
         for import in imports {
-            if let Some(resolved) = adapter
-                .resolve_import(&import, &canonical, &context)
+            if !self.import_filter.allows(&import.kind, &canonical, depth) {
+                continue;
+            }
+            let resolved: Vec<ResolvedImport> = file_adapter
+                .resolve_import_all(&import, &canonical, &context)
                 .await?
-            {
-                if resolved.is_local {
-                    graph
-                        .lock()
-                        .await
-                        .add_edge(&canonical, &resolved.path, import.clone());
-
-                    if resolved.is_asset {
-                        graph.lock().await.add_asset(&resolved.path);
-                        log::debug!(
-                            "Found asset dependency: {} -> {}",
-                            canonical.display(),
-                            resolved.path.display()
-                        );
+                .into_iter()
+                .filter(|resolved| resolved.is_local)
+                .collect();
+            if resolved.is_empty() {
+                graph.lock().await.add_external(&canonical, import);
+                continue;
+            }
+
+            for resolved in resolved {
+                if !matches_extensions(&resolved.path, &self.extensions) {
+                    log::debug!(
+                        "Skipping {} - extension not in filter",
+                        resolved.path.display()
+                    );
+                    continue;
+                }
+
+                graph
+                    .lock()
+                    .await
+                    .add_edge(&canonical, &resolved.path, import.clone());
+
+                if resolved.is_asset {
+                    graph.lock().await.add_asset(&resolved.path);
+                    log::debug!(
+                        "Found asset dependency: {} -> {}",
+                        canonical.display(),
+                        resolved.path.display()
+                    );
+                    if !self.include_assets || self.adapter_for(&resolved.path, &adapter).is_none()
+                    {
                         continue;
                     }
+                }
 
-                    let adapter = adapter.clone();
-                    let context = context.clone();
-                    let graph = graph.clone();
+                let adapter = adapter.clone();
+                let context = context.clone();
+                let graph = graph.clone();
 
-                    let task =
-                        self.traverse_recursive(resolved.path, adapter, context, graph, depth + 1);
-                    tasks.push(task);
-                }
+                let task =
+                    self.traverse_recursive(resolved.path, adapter, context, graph, depth + 1);
+                tasks.push(task);
             }
         }
 
@@ -499,8 +802,203 @@ impl DependencyTraverser {
             }
         }
 
-        self.in_progress.remove(&canonical);
-
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::css::CssAdapter;
+    use crate::adapters::javascript::JsAdapter;
+    use crate::core::fs::MemoryFileSystem;
+
+    async fn traverse(
+        traverser: DependencyTraverser,
+        entries: &[&str],
+        files: &[(&str, &str)],
+    ) -> DependencyGraph {
+        let context = Arc::new(AnalysisContext {
+            fs: Arc::new(MemoryFileSystem::new(files.iter().copied())),
+        });
+        let entries: Vec<PathBuf> = entries.iter().map(PathBuf::from).collect();
+        traverser
+            .traverse_many(&entries, Arc::new(JsAdapter::new()), context)
+            .await
+            .unwrap()
+    }
+
+    fn nodes(graph: &DependencyGraph, entry: &str) -> Vec<String> {
+        let mut nodes: Vec<String> = graph
+            .reachable_from(Path::new(entry))
+            .into_iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        nodes.sort();
+        nodes
+    }
+
+    fn edge(graph: &mut DependencyGraph, from: &str, to: &str) {
+        let import = ImportStatement {
+            specifier: to.to_string(),
+            kind: ImportKind::EsModule,
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        };
+        graph.add_edge(Path::new(from), Path::new(to), import);
+    }
+
+    #[test]
+    fn test_reachability_lists_entries_in_order() {
+        let mut graph = DependencyGraph::with_entries(vec![
+            PathBuf::from("/a/main.ts"),
+            PathBuf::from("/a/cli.ts"),
+        ]);
+        edge(&mut graph, "/a/main.ts", "/a/shared.ts");
+        edge(&mut graph, "/a/cli.ts", "/a/shared.ts");
+        edge(&mut graph, "/a/cli.ts", "/a/args.ts");
+        edge(&mut graph, "/a/shared.ts", "/a/main.ts");
+
+        let reachability = graph.reachability();
+        let entries = |file: &str| {
+            reachability[Path::new(file)]
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(entries("/a/shared.ts"), vec!["/a/main.ts", "/a/cli.ts"]);
+        assert_eq!(entries("/a/main.ts"), vec!["/a/main.ts", "/a/cli.ts"]);
+        assert_eq!(entries("/a/args.ts"), vec!["/a/cli.ts"]);
+        assert_eq!(entries("/a/cli.ts"), vec!["/a/cli.ts"]);
+    }
+
+    #[test]
+    fn test_path_score_depth_uses_closest_entry() {
+        let deep = format!("/p/web/{}file.ts", "d/".repeat(14));
+        let entries = [PathBuf::from("/p/main.ts"), PathBuf::from("/p/web/main.ts")];
+
+        let score = PathScore::from_path(Path::new(&deep), &entries);
+        assert_eq!(score.depth, 15);
+        assert!(!score.should_skip());
+        assert!(PathScore::from_path(Path::new(&deep), &entries[..1]).should_skip());
+        assert_eq!(
+            PathScore::from_path(Path::new("/q/x.ts"), &entries).depth,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_traversal_marks_files_on_import_cycles() {
+        let files = [
+            ("/app/main.ts", "import './b';\nimport './d';"),
+            ("/app/b.ts", "import './c';"),
+            ("/app/c.ts", "import './b';\nimport './e';"),
+            ("/app/d.ts", "import './d';"),
+            ("/app/e.ts", "export const e = 1;"),
+        ];
+        let graph = traverse(DependencyTraverser::new(), &["/app/main.ts"], &files).await;
+
+        let mut circular: Vec<String> = graph
+            .circular_deps
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        circular.sort();
+        assert_eq!(circular, vec!["/app/b.ts", "/app/c.ts", "/app/d.ts"]);
+    }
+
+    #[test]
+    fn test_matches_extensions() {
+        let exts = |list: &[&str]| list.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+
+        assert!(matches_extensions(Path::new("/a/main.ts"), &[]));
+        assert!(matches_extensions(Path::new("/a/main.TS"), &exts(&["ts"])));
+        assert!(matches_extensions(
+            Path::new("/a/env.d.ts"),
+            &exts(&[".d.ts"])
+        ));
+        assert!(!matches_extensions(
+            Path::new("/a/main.ts"),
+            &exts(&["d.ts"])
+        ));
+        // The extension must follow a dot, not just end the name
+        assert!(!matches_extensions(
+            Path::new("/a/contacts"),
+            &exts(&["ts"])
+        ));
+        assert!(!matches_extensions(Path::new("/a/ts"), &exts(&["ts"])));
+        assert!(!matches_extensions(
+            Path::new("/a/style.css"),
+            &exts(&["ts", "tsx"])
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_types_filter_follows_reexports_and_declaration_files() {
+        let files = [
+            (
+                "/app/main.ts",
+                "import type { Button } from './types';\nimport { run } from './runtime';",
+            ),
+            (
+                "/app/types/index.ts",
+                "export * from './button';\nimport { helper } from '../helper';",
+            ),
+            ("/app/types/button.ts", "export interface Button {}"),
+            ("/app/index.d.ts", "import { Config } from './config';"),
+            ("/app/config.ts", "export interface Config {}"),
+            ("/app/runtime.ts", "export const run = 1;"),
+            ("/app/helper.ts", "export const helper = 1;"),
+        ];
+        let traverser = DependencyTraverser::new().with_import_filter(ImportFilter::Types);
+        let graph = traverse(traverser, &["/app/main.ts", "/app/index.d.ts"], &files).await;
+
+        assert_eq!(
+            nodes(&graph, "/app/main.ts"),
+            vec![
+                "/app/main.ts",
+                "/app/types/button.ts",
+                "/app/types/index.ts"
+            ]
+        );
+        assert_eq!(
+            nodes(&graph, "/app/index.d.ts"),
+            vec!["/app/config.ts", "/app/index.d.ts"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assets_are_followed_only_when_included() {
+        let files = [
+            (
+                "/app/main.ts",
+                "import './styles.css';\nimport logo from './logo.png';",
+            ),
+            ("/app/styles.css", "@import './base.css';"),
+            ("/app/base.css", "body { margin: 0; }"),
+            ("/app/logo.png", ""),
+        ];
+        let traverser =
+            || DependencyTraverser::new().with_adapters(vec![Arc::new(CssAdapter::new())]);
+
+        let graph = traverse(traverser(), &["/app/main.ts"], &files).await;
+        assert_eq!(
+            nodes(&graph, "/app/main.ts"),
+            vec!["/app/logo.png", "/app/main.ts", "/app/styles.css"]
+        );
+        assert!(graph.assets.contains(Path::new("/app/styles.css")));
+
+        let graph = traverse(traverser().with_assets(true), &["/app/main.ts"], &files).await;
+        assert_eq!(
+            nodes(&graph, "/app/main.ts"),
+            vec![
+                "/app/base.css",
+                "/app/logo.png",
+                "/app/main.ts",
+                "/app/styles.css"
+            ]
+        );
+    }
+}
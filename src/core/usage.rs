@@ -0,0 +1,286 @@
+use crate::core::language::{ImportBinding, ImportKind, ImportStatement};
+use crate::core::traverser::DependencyGraph;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+/// The exports of a file that its importers use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Usage {
+    /// The whole module is used, e.g. through a namespace import or `require`
+    All,
+    /// Only these export names are used; empty for side-effect imports
+    Names(BTreeSet<String>),
+}
+
+impl Usage {
+    pub fn includes(&self, name: &str) -> bool {
+        match self {
+            Usage::All => true,
+            Usage::Names(names) => names.contains(name),
+        }
+    }
+
+    /// Adds `other` to this usage, returning whether anything changed
+    fn merge(&mut self, other: Usage) -> bool {
+        match (&mut *self, other) {
+            (Usage::All, _) => false,
+            (_, Usage::All) => {
+                *self = Usage::All;
+                true
+            }
+            (Usage::Names(names), Usage::Names(other)) => {
+                let before = names.len();
+                names.extend(other);
+                names.len() != before
+            }
+        }
+    }
+}
+
+/// Works out which exports of every file in `graph` are used, starting from
+/// the entries, which are used in full. Re-exports only pass on the names the
+/// re-exporting file's own importers use.
+///
+/// `live_locals` narrows a file's imports to the local names its remaining
+/// code still references; files without an entry use all of their imports.
+pub fn used_exports(
+    graph: &DependencyGraph,
+    live_locals: &HashMap<PathBuf, HashSet<String>>,
+) -> HashMap<PathBuf, Usage> {
+    let mut usage: HashMap<PathBuf, Usage> = graph
+        .entry_points
+        .iter()
+        .map(|entry| (entry.clone(), Usage::All))
+        .collect();
+
+    // Usage only grows, so this settles after at most a few passes per
+    // re-export level
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (from, deps) in &graph.adj_list {
+            let Some(from_usage) = usage.get(from).cloned() else {
+                continue;
+            };
+            let live = live_locals.get(from);
+            for (to, import) in deps {
+                let used = import_usage(import, &from_usage, live);
+                match usage.get_mut(to) {
+                    Some(existing) => changed |= existing.merge(used),
+                    None => {
+                        usage.insert(to.clone(), used);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    usage
+}
+
+/// Exports of the imported file that `import` uses, given how the importing
+/// file itself is used
+fn import_usage(
+    import: &ImportStatement,
+    from_usage: &Usage,
+    live: Option<&HashSet<String>>,
+) -> Usage {
+    if import.bindings.is_empty() {
+        // Side-effect imports use nothing; `require`, dynamic imports, type
+        // queries and assets may use anything
+        return match import.kind {
+            ImportKind::EsModule | ImportKind::ReExport => Usage::Names(BTreeSet::new()),
+            _ => Usage::All,
+        };
+    }
+
+    let is_live = |local: &String| live.is_none_or(|live| live.contains(local));
+    let mut names = BTreeSet::new();
+    for binding in &import.bindings {
+        match binding {
+            ImportBinding::Default { local, .. } => {
+                if is_live(local) {
+                    names.insert("default".to_string());
+                }
+            }
+            ImportBinding::Named {
+                imported, local, ..
+            } => {
+                if is_live(local) {
+                    names.insert(imported.clone());
+                }
+            }
+            ImportBinding::Namespace { local, .. } => {
+                if is_live(local) {
+                    return Usage::All;
+                }
+            }
+            ImportBinding::ReExport {
+                imported, exported, ..
+            } => {
+                if from_usage.includes(exported) {
+                    names.insert(imported.clone());
+                }
+            }
+            ImportBinding::ReExportNamespace { exported } => {
+                if from_usage.includes(exported) {
+                    return Usage::All;
+                }
+            }
+            // `export *` never forwards the default export
+            ImportBinding::ReExportAll => match from_usage {
+                Usage::All => return Usage::All,
+                Usage::Names(used) => {
+                    names.extend(used.iter().filter(|n| *n != "default").cloned());
+                }
+            },
+        }
+    }
+    Usage::Names(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn import(kind: ImportKind, bindings: Vec<ImportBinding>) -> ImportStatement {
+        ImportStatement {
+            specifier: String::new(),
+            kind,
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings,
+        }
+    }
+
+    fn named(name: &str) -> ImportBinding {
+        ImportBinding::Named {
+            imported: name.into(),
+            local: name.into(),
+            type_only: false,
+        }
+    }
+
+    fn re_export(imported: &str, exported: &str) -> ImportBinding {
+        ImportBinding::ReExport {
+            imported: imported.into(),
+            exported: exported.into(),
+            type_only: false,
+        }
+    }
+
+    fn names(names: &[&str]) -> Usage {
+        Usage::Names(names.iter().map(|n| n.to_string()).collect())
+    }
+
+    fn graph(edges: Vec<(&str, &str, ImportStatement)>) -> DependencyGraph {
+        let mut graph = DependencyGraph::new(PathBuf::from("main.ts"));
+        for (from, to, import) in edges {
+            graph.add_edge(from.as_ref(), to.as_ref(), import);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_barrel_chains_forward_only_used_names() {
+        let graph = graph(vec![
+            (
+                "main.ts",
+                "index.ts",
+                import(
+                    ImportKind::EsModule,
+                    vec![
+                        named("Button"),
+                        ImportBinding::Default {
+                            local: "App".into(),
+                            type_only: false,
+                        },
+                    ],
+                ),
+            ),
+            // export { Button, Card } from './components'
+            (
+                "index.ts",
+                "components.ts",
+                import(
+                    ImportKind::ReExport,
+                    vec![re_export("Button", "Button"), re_export("Card", "Card")],
+                ),
+            ),
+            // export { default as Button } from './button'
+            (
+                "components.ts",
+                "button.ts",
+                import(ImportKind::ReExport, vec![re_export("default", "Button")]),
+            ),
+            // export { App as default } from './app'
+            (
+                "index.ts",
+                "app.ts",
+                import(ImportKind::ReExport, vec![re_export("App", "default")]),
+            ),
+            // export * from './utils'
+            (
+                "index.ts",
+                "utils.ts",
+                import(ImportKind::ReExport, vec![ImportBinding::ReExportAll]),
+            ),
+        ]);
+
+        let usage = used_exports(&graph, &HashMap::new());
+
+        assert_eq!(usage[Path::new("main.ts")], Usage::All);
+        assert_eq!(usage[Path::new("index.ts")], names(&["Button", "default"]));
+        assert_eq!(usage[Path::new("components.ts")], names(&["Button"]));
+        assert_eq!(usage[Path::new("button.ts")], names(&["default"]));
+        assert_eq!(usage[Path::new("app.ts")], names(&["App"]));
+        // `export *` never forwards the default export
+        assert_eq!(usage[Path::new("utils.ts")], names(&["Button"]));
+    }
+
+    #[test]
+    fn test_namespace_imports_use_the_whole_module() {
+        let graph = graph(vec![
+            (
+                "main.ts",
+                "lib.ts",
+                import(
+                    ImportKind::EsModule,
+                    vec![ImportBinding::Namespace {
+                        local: "lib".into(),
+                        type_only: false,
+                    }],
+                ),
+            ),
+            (
+                "lib.ts",
+                "helpers.ts",
+                import(ImportKind::ReExport, vec![ImportBinding::ReExportAll]),
+            ),
+            (
+                "lib.ts",
+                "format.ts",
+                import(
+                    ImportKind::ReExport,
+                    vec![ImportBinding::ReExportNamespace {
+                        exported: "format".into(),
+                    }],
+                ),
+            ),
+        ]);
+
+        let usage = used_exports(&graph, &HashMap::new());
+        assert_eq!(usage[Path::new("lib.ts")], Usage::All);
+        assert_eq!(usage[Path::new("helpers.ts")], Usage::All);
+        assert_eq!(usage[Path::new("format.ts")], Usage::All);
+
+        // A namespace whose local is no longer referenced uses nothing
+        let live = HashMap::from([(PathBuf::from("main.ts"), HashSet::new())]);
+        let usage = used_exports(&graph, &live);
+        assert_eq!(usage[Path::new("lib.ts")], names(&[]));
+        assert_eq!(usage[Path::new("helpers.ts")], names(&[]));
+    }
+}
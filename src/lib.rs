@@ -1,4 +1,9 @@
 pub mod adapters;
+pub mod bundler;
 pub mod cli;
+pub mod config;
 pub mod core;
 pub mod output;
+
+pub use bundler::{Bundle, BundleProgress, Bundler};
+pub use output::{Detail, OutputFormat};
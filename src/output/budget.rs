@@ -0,0 +1,309 @@
+use super::{format_path, BundleNotes};
+use crate::core::traverser::DependencyGraph;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Files are only truncated when at least this many tokens are left for them
+const MIN_TRUNCATED_TOKENS: usize = 200;
+
+/// Rough cost of the heading and code fence around each file
+const PER_FILE_OVERHEAD_TOKENS: usize = 8;
+
+/// Estimates how many tokens a piece of text uses in an LLM prompt
+pub trait Tokenizer: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Local approximation of a BPE tokenizer (cl100k-like).
+///
+/// Words are merged with one leading space and split into ~4 character pieces,
+/// numbers into groups of 3 digits, punctuation runs into pairs, and each line
+/// break costs a token. Non-ASCII characters count one token each.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ApproxTokenizer;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum CharClass {
+    Word,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+    Other,
+}
+
+fn classify(c: char) -> CharClass {
+    match c {
+        '\n' => CharClass::Newline,
+        c if c.is_whitespace() => CharClass::Space,
+        c if c.is_ascii_digit() => CharClass::Digit,
+        c if c.is_ascii_alphabetic() || c == '_' => CharClass::Word,
+        c if c.is_ascii_punctuation() => CharClass::Punct,
+        _ => CharClass::Other,
+    }
+}
+
+impl Tokenizer for ApproxTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        let mut tokens = 0;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let class = classify(c);
+            let mut run: usize = 1;
+            while chars.peek().is_some_and(|&n| classify(n) == class) {
+                chars.next();
+                run += 1;
+            }
+
+            tokens += match class {
+                CharClass::Word => run.div_ceil(4),
+                CharClass::Digit => run.div_ceil(3),
+                CharClass::Punct => run.div_ceil(2),
+                CharClass::Newline => run,
+                // A single space is merged into the following word
+                CharClass::Space if run == 1 => 0,
+                CharClass::Space => run.div_ceil(8),
+                CharClass::Other => run,
+            };
+        }
+
+        tokens
+    }
+}
+
+/// Result of fitting a bundle into a token budget
+#[derive(Debug, Default)]
+pub struct BudgetReport {
+    pub max_tokens: usize,
+    pub used_tokens: usize,
+    pub included: usize,
+    pub truncated: usize,
+    pub omitted: usize,
+}
+
+/// Shortest import distance of every reachable file from the nearest entry point
+pub fn distances_from_entry(graph: &DependencyGraph) -> HashMap<PathBuf, usize> {
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for entry in &graph.entry_points {
+        distances.insert(entry.clone(), 0);
+        queue.push_back(entry.clone());
+    }
+
+    while let Some(path) = queue.pop_front() {
+        let distance = distances[&path];
+        if let Some(deps) = graph.adj_list.get(&path) {
+            for (dep, _) in deps {
+                if !distances.contains_key(dep) {
+                    distances.insert(dep.clone(), distance + 1);
+                    queue.push_back(dep.clone());
+                }
+            }
+        }
+    }
+
+    distances
+}
+
+/// Drops or truncates the files farthest from the entry point until the
+/// estimated token count of `files` fits into `max_tokens`.
+///
+/// Files are kept in order of graph distance from the entry point (ties broken
+/// by path). The first file that doesn't fit is truncated if enough budget is
+/// left, and it and every farther file are otherwise omitted. Omitted and
+/// truncated files are recorded in `notes`.
+pub fn apply_token_budget(
+    graph: &DependencyGraph,
+    files: &mut HashMap<PathBuf, String>,
+    max_tokens: usize,
+    tokenizer: &dyn Tokenizer,
+    git_root: Option<&Path>,
+    notes: &mut BundleNotes,
+) -> BudgetReport {
+    let distances = distances_from_entry(graph);
+    let mut ordered: Vec<PathBuf> = files.keys().cloned().collect();
+    ordered.sort_by(|a, b| {
+        let da = distances.get(a).copied().unwrap_or(usize::MAX);
+        let db = distances.get(b).copied().unwrap_or(usize::MAX);
+        da.cmp(&db).then_with(|| a.cmp(b))
+    });
+
+    let mut report = BudgetReport {
+        max_tokens,
+        ..Default::default()
+    };
+    let mut exhausted = false;
+
+    for path in ordered {
+        if exhausted {
+            files.remove(&path);
+            notes.omit(&path, "token budget exceeded");
+            report.omitted += 1;
+            continue;
+        }
+
+        let overhead =
+            PER_FILE_OVERHEAD_TOKENS + tokenizer.count_tokens(&format_path(&path, git_root));
+        let content_tokens = tokenizer.count_tokens(&files[&path]);
+        let remaining = max_tokens.saturating_sub(report.used_tokens);
+
+        if overhead + content_tokens <= remaining {
+            report.used_tokens += overhead + content_tokens;
+            report.included += 1;
+            continue;
+        }
+
+        exhausted = true;
+        if remaining >= overhead + MIN_TRUNCATED_TOKENS {
+            let content = files.get_mut(&path).expect("path comes from files");
+            let (truncated, kept_lines, total_lines, tokens) =
+                truncate_to_tokens(content, remaining - overhead, tokenizer);
+            *content = truncated;
+            notes.abbreviate(
+                &path,
+                format!("truncated to {} of {} lines", kept_lines, total_lines),
+            );
+            report.used_tokens += overhead + tokens;
+            report.included += 1;
+            report.truncated += 1;
+        } else {
+            files.remove(&path);
+            notes.omit(&path, "token budget exceeded");
+            report.omitted += 1;
+        }
+    }
+
+    report
+}
+
+/// Keeps whole lines from the start of `content` while they fit into `budget`,
+/// leaving room for a trailing marker line
+fn truncate_to_tokens(
+    content: &str,
+    budget: usize,
+    tokenizer: &dyn Tokenizer,
+) -> (String, usize, usize, usize) {
+    let total_lines = content.lines().count();
+    let marker =
+        |truncated: usize| format!("... {} more lines truncated by packlet ...", truncated);
+    // The marker never has more digits than with every line truncated
+    let marker_budget = tokenizer.count_tokens(&marker(total_lines));
+    let mut kept = String::new();
+    let mut used = 0;
+    let mut kept_lines = 0;
+
+    for line in content.lines() {
+        let cost = tokenizer.count_tokens(line) + 1;
+        if used + cost + marker_budget > budget {
+            break;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+        used += cost;
+        kept_lines += 1;
+    }
+
+    let marker = marker(total_lines - kept_lines);
+    used += tokenizer.count_tokens(&marker);
+    kept.push_str(&marker);
+
+    (kept, kept_lines, total_lines, used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language::{ImportKind, ImportStatement};
+
+    #[test]
+    fn test_approx_tokenizer() {
+        let tokenizer = ApproxTokenizer;
+        assert_eq!(tokenizer.count_tokens(""), 0);
+        assert_eq!(tokenizer.count_tokens("hello world"), 4);
+        assert_eq!(tokenizer.count_tokens("x = 1234;\n"), 6);
+    }
+
+    #[test]
+    fn test_truncate_keeps_leading_lines() {
+        let content = (0..100)
+            .map(|i| format!("const value{} = {};", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (truncated, kept, total, used) = truncate_to_tokens(&content, 100, &ApproxTokenizer);
+        assert_eq!(total, 100);
+        assert!(kept > 0 && kept < total);
+        assert!(used <= 100);
+        assert!(truncated.starts_with("const value0 = 0;"));
+        assert!(truncated.ends_with("more lines truncated by packlet ..."));
+    }
+
+    /// Counts every byte as a token
+    struct ByteTokenizer;
+
+    impl Tokenizer for ByteTokenizer {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.len()
+        }
+    }
+
+    #[test]
+    fn test_budget_drops_farthest_files_and_truncates_the_last_that_fits() {
+        let mut graph = DependencyGraph::new(PathBuf::from("/r/main.ts"));
+        for (from, to) in [
+            ("/r/main.ts", "/r/b.ts"),
+            ("/r/main.ts", "/r/a.ts"),
+            ("/r/a.ts", "/r/c.ts"),
+        ] {
+            let import = ImportStatement {
+                specifier: to.to_string(),
+                kind: ImportKind::EsModule,
+                line: 1,
+                column: 0,
+                raw: String::new(),
+                bindings: Vec::new(),
+            };
+            graph.add_edge(Path::new(from), Path::new(to), import);
+        }
+        assert_eq!(distances_from_entry(&graph)[Path::new("/r/c.ts")], 2);
+
+        let line = format!("{}\n", "x".repeat(19));
+        let mut files: HashMap<PathBuf, String> = [
+            ("/r/main.ts", "m".repeat(50)),
+            ("/r/a.ts", "a".repeat(50)),
+            ("/r/b.ts", line.repeat(50)),
+            // Small enough to fit, but farthest from the entry
+            ("/r/c.ts", "c".to_string()),
+        ]
+        .into_iter()
+        .map(|(path, content)| (PathBuf::from(path), content))
+        .collect();
+        let mut notes = BundleNotes::default();
+
+        // main.ts and a.ts cost 65 and 62 with their overhead, leaving 300 for b.ts
+        let report = apply_token_budget(
+            &graph,
+            &mut files,
+            427,
+            &ByteTokenizer,
+            Some(Path::new("/r")),
+            &mut notes,
+        );
+
+        assert_eq!(
+            (report.included, report.truncated, report.omitted),
+            (3, 1, 1)
+        );
+        assert!(report.used_tokens <= 427);
+        assert_eq!(files[Path::new("/r/main.ts")], "m".repeat(50));
+        assert_eq!(files[Path::new("/r/a.ts")], "a".repeat(50));
+        assert!(files[Path::new("/r/b.ts")].ends_with("... 38 more lines truncated by packlet ..."));
+        assert!(!files.contains_key(Path::new("/r/c.ts")));
+        assert_eq!(
+            notes.abbreviated[Path::new("/r/b.ts")],
+            "truncated to 12 of 50 lines"
+        );
+        assert_eq!(notes.omitted[Path::new("/r/c.ts")], "token budget exceeded");
+        assert_eq!(notes.omitted.len(), 1);
+    }
+}
@@ -0,0 +1,251 @@
+use super::format_path;
+use crate::core::language::ImportKind;
+use crate::core::traverser::DependencyGraph;
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Renders a `DependencyGraph` as a Graphviz DOT digraph
+#[derive(Default)]
+pub struct DotFormatter {
+    /// Group nodes into `cluster_*` subgraphs by their parent directory
+    pub cluster_by_directory: bool,
+}
+
+impl DotFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_clusters(mut self, cluster_by_directory: bool) -> Self {
+        self.cluster_by_directory = cluster_by_directory;
+        self
+    }
+
+    pub fn format_graph(&self, graph: &DependencyGraph, git_root: Option<&Path>) -> Result<String> {
+        let mut output = String::new();
+
+        writeln!(output, "digraph packlet {{")?;
+        writeln!(output, "    rankdir=LR;")?;
+        writeln!(output, "    node [shape=box, fontname=\"Helvetica\"];")?;
+        writeln!(output, "    edge [fontname=\"Helvetica\"];")?;
+
+        let mut nodes = BTreeSet::new();
+        nodes.extend(graph.entry_points.iter().cloned());
+        for (from, deps) in &graph.adj_list {
+            nodes.insert(from.clone());
+            nodes.extend(deps.iter().map(|(to, _)| to.clone()));
+        }
+
+        if self.cluster_by_directory {
+            let mut clusters: BTreeMap<String, Vec<&PathBuf>> = BTreeMap::new();
+            for node in &nodes {
+                let dir = node
+                    .parent()
+                    .map(|p| format_path(p, git_root))
+                    .unwrap_or_default();
+                clusters.entry(dir).or_default().push(node);
+            }
+
+            for (i, (dir, members)) in clusters.iter().enumerate() {
+                writeln!(output, "    subgraph cluster_{} {{", i)?;
+                writeln!(output, "        label=\"{}\";", escape(dir))?;
+                writeln!(output, "        style=rounded;")?;
+                for node in members {
+                    self.write_node(&mut output, graph, node, git_root, "        ")?;
+                }
+                writeln!(output, "    }}")?;
+            }
+        } else {
+            for node in &nodes {
+                self.write_node(&mut output, graph, node, git_root, "    ")?;
+            }
+        }
+
+        let mut sorted_sources: Vec<_> = graph.adj_list.iter().collect();
+        sorted_sources.sort_by(|a, b| a.0.cmp(b.0));
+        for (from, deps) in sorted_sources {
+            for (to, import) in deps {
+                let mut attrs = Vec::new();
+                match import.kind {
+                    ImportKind::Dynamic => attrs.push("style=dashed"),
+                    ImportKind::TypeOnly => attrs.push("style=dotted"),
+                    ImportKind::ReExport => attrs.push("arrowhead=empty"),
+                    ImportKind::Asset => attrs.push("color=gray50"),
+                    _ => {}
+                }
+
+                write!(
+                    output,
+                    "    \"{}\" -> \"{}\"",
+                    escape(&format_path(from, git_root)),
+                    escape(&format_path(to, git_root))
+                )?;
+                if attrs.is_empty() {
+                    writeln!(output, ";")?;
+                } else {
+                    writeln!(output, " [{}];", attrs.join(", "))?;
+                }
+            }
+        }
+
+        writeln!(output, "}}")?;
+        Ok(output)
+    }
+
+    fn write_node(
+        &self,
+        output: &mut String,
+        graph: &DependencyGraph,
+        node: &Path,
+        git_root: Option<&Path>,
+        indent: &str,
+    ) -> Result<()> {
+        let id = format_path(node, git_root);
+        let label = if self.cluster_by_directory {
+            node.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| id.clone())
+        } else {
+            id.clone()
+        };
+
+        let mut attrs = vec![format!("label=\"{}\"", escape(&label))];
+        if graph.is_entry(node) {
+            attrs.push("style=bold".to_string());
+            attrs.push("penwidth=2".to_string());
+        }
+        if graph.assets.contains(node) {
+            attrs.push("shape=note".to_string());
+            attrs.push("color=gray50".to_string());
+        }
+        if graph.circular_deps.contains(node) {
+            attrs.push("color=red".to_string());
+            attrs.push("fontcolor=red".to_string());
+        }
+
+        writeln!(
+            output,
+            "{}\"{}\" [{}];",
+            indent,
+            escape(&id),
+            attrs.join(", ")
+        )?;
+        Ok(())
+    }
+}
+
+/// Escape a value for use inside a double-quoted DOT string
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language::ImportStatement;
+
+    fn import(kind: ImportKind) -> ImportStatement {
+        ImportStatement {
+            specifier: String::new(),
+            kind,
+            line: 1,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    fn sample_graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new(PathBuf::from("/repo/src/main.ts"));
+        let main = Path::new("/repo/src/main.ts");
+        for (to, kind) in [
+            ("/repo/src/util.ts", ImportKind::EsModule),
+            ("/repo/src/lazy.ts", ImportKind::Dynamic),
+            ("/repo/src/types.ts", ImportKind::TypeOnly),
+            ("/repo/src/lib/index.ts", ImportKind::ReExport),
+            ("/repo/assets/logo.png", ImportKind::Asset),
+        ] {
+            graph.add_edge(main, Path::new(to), import(kind));
+        }
+        graph.add_edge(
+            Path::new("/repo/src/util.ts"),
+            main,
+            import(ImportKind::EsModule),
+        );
+        graph.add_edge(
+            Path::new("/repo/src/util.ts"),
+            Path::new("/repo/src/we\"ird\\name.ts"),
+            import(ImportKind::EsModule),
+        );
+        graph.add_asset(Path::new("/repo/assets/logo.png"));
+        graph.mark_circular(main);
+        graph
+    }
+
+    #[test]
+    fn test_format_graph() {
+        let output = DotFormatter::new()
+            .format_graph(&sample_graph(), Some(Path::new("/repo")))
+            .unwrap();
+
+        let expected = r#"digraph packlet {
+    rankdir=LR;
+    node [shape=box, fontname="Helvetica"];
+    edge [fontname="Helvetica"];
+    "assets/logo.png" [label="assets/logo.png", shape=note, color=gray50];
+    "src/lazy.ts" [label="src/lazy.ts"];
+    "src/lib/index.ts" [label="src/lib/index.ts"];
+    "src/main.ts" [label="src/main.ts", style=bold, penwidth=2, color=red, fontcolor=red];
+    "src/types.ts" [label="src/types.ts"];
+    "src/util.ts" [label="src/util.ts"];
+    "src/we\"ird\\name.ts" [label="src/we\"ird\\name.ts"];
+    "src/main.ts" -> "src/util.ts";
+    "src/main.ts" -> "src/lazy.ts" [style=dashed];
+    "src/main.ts" -> "src/types.ts" [style=dotted];
+    "src/main.ts" -> "src/lib/index.ts" [arrowhead=empty];
+    "src/main.ts" -> "assets/logo.png" [color=gray50];
+    "src/util.ts" -> "src/main.ts";
+    "src/util.ts" -> "src/we\"ird\\name.ts";
+}
+"#;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_format_graph_with_clusters() {
+        let output = DotFormatter::new()
+            .with_clusters(true)
+            .format_graph(&sample_graph(), Some(Path::new("/repo")))
+            .unwrap();
+
+        let clusters: String = output
+            .lines()
+            .skip(4)
+            .take_while(|line| !line.contains("->"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let expected = r#"    subgraph cluster_0 {
+        label="assets";
+        style=rounded;
+        "assets/logo.png" [label="logo.png", shape=note, color=gray50];
+    }
+    subgraph cluster_1 {
+        label="src";
+        style=rounded;
+        "src/lazy.ts" [label="lazy.ts"];
+        "src/main.ts" [label="main.ts", style=bold, penwidth=2, color=red, fontcolor=red];
+        "src/types.ts" [label="types.ts"];
+        "src/util.ts" [label="util.ts"];
+        "src/we\"ird\\name.ts" [label="we\"ird\\name.ts"];
+    }
+    subgraph cluster_2 {
+        label="src/lib";
+        style=rounded;
+        "src/lib/index.ts" [label="index.ts"];
+    }
+"#;
+        assert_eq!(clusters, expected);
+    }
+}
@@ -1,9 +1,34 @@
 use crate::core::traverser::DependencyGraph;
 use anyhow::Result;
-use std::collections::HashMap;
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
+pub mod budget;
+mod dot;
+mod xml;
+
+pub use budget::{ApproxTokenizer, Tokenizer};
+pub use dot::DotFormatter;
+pub use xml::XmlFormatter;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Markdown,
+    Xml,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Detail {
+    /// Every file in full
+    Full,
+    /// Dependencies beyond --full-depth as declarations only
+    Signatures,
+}
+
 /// Convert absolute path to relative path from git root, or return display string if not in git repo
 fn format_path(path: &Path, git_root: Option<&Path>) -> String {
     if let Some(root) = git_root {
@@ -14,35 +39,164 @@ fn format_path(path: &Path, git_root: Option<&Path>) -> String {
     path.display().to_string()
 }
 
+/// Per-file remarks about how a bundle deviates from the full graph, and
+/// material added beside it
+#[derive(Debug, Default, Clone)]
+pub struct BundleNotes {
+    /// Files in the graph whose contents were left out, with the reason
+    pub omitted: BTreeMap<PathBuf, String>,
+    /// Files whose contents were shortened, with a description
+    pub abbreviated: BTreeMap<PathBuf, String>,
+    /// Type declarations of the external packages the bundle imports from
+    pub external_api: Option<ExternalApi>,
+}
+
+/// Declaration files rendered in the "External API Surface" section
+#[derive(Debug, Default, Clone)]
+pub struct ExternalApi {
+    /// Declaration file contents by package name
+    pub packages: BTreeMap<String, Vec<(PathBuf, String)>>,
+    /// Declaration files left out by the size cap
+    pub omitted: Vec<PathBuf>,
+}
+
+impl BundleNotes {
+    pub fn omit(&mut self, path: &Path, reason: impl Into<String>) {
+        self.omitted.insert(path.to_path_buf(), reason.into());
+    }
+
+    pub fn abbreviate(&mut self, path: &Path, description: impl Into<String>) {
+        self.abbreviated
+            .insert(path.to_path_buf(), description.into());
+    }
+
+    pub fn is_omitted(&self, path: &Path) -> bool {
+        self.omitted.contains_key(path)
+    }
+
+    pub fn is_abbreviated(&self, path: &Path) -> bool {
+        self.abbreviated.contains_key(path)
+    }
+}
+
 pub trait OutputFormatter: Send + Sync {
-    fn format(&self, graph: &DependencyGraph, files: &HashMap<PathBuf, String>) -> Result<String>;
+    fn format(&self, graph: &DependencyGraph, files: &HashMap<PathBuf, String>) -> Result<String> {
+        self.format_with_git_root(graph, files, None)
+    }
+
     fn format_with_git_root(
         &self,
         graph: &DependencyGraph,
         files: &HashMap<PathBuf, String>,
         git_root: Option<&Path>,
+    ) -> Result<String> {
+        self.format_with_notes(graph, files, git_root, &BundleNotes::default())
+    }
+
+    fn format_with_notes(
+        &self,
+        graph: &DependencyGraph,
+        files: &HashMap<PathBuf, String>,
+        git_root: Option<&Path>,
+        notes: &BundleNotes,
     ) -> Result<String>;
 }
 
 pub struct MarkdownFormatter;
 
+/// Renders the ASCII dependency tree, one tree per entry point
+struct TreeRenderer<'a> {
+    graph: &'a DependencyGraph,
+    git_root: Option<&'a Path>,
+    notes: &'a BundleNotes,
+    /// Files on the branch currently being written
+    ancestors: Vec<PathBuf>,
+    /// Files whose dependencies were already written
+    expanded: HashSet<PathBuf>,
+    output: String,
+}
+
+impl TreeRenderer<'_> {
+    fn write_node(&mut self, path: &Path, line_prefix: &str, child_prefix: &str) -> Result<()> {
+        let label = format_path(path, self.git_root);
+
+        if self.ancestors.iter().any(|a| a == path) {
+            writeln!(self.output, "{}{} (circular)", line_prefix, label)?;
+            return Ok(());
+        }
+
+        let mut deps: Vec<&PathBuf> = self
+            .graph
+            .adj_list
+            .get(path)
+            .map(|deps| deps.iter().map(|(p, _)| p).collect())
+            .unwrap_or_default();
+        deps.sort();
+        deps.dedup();
+
+        let marker = if self.notes.is_omitted(path) {
+            " (omitted)"
+        } else if self.notes.is_abbreviated(path) {
+            " (abbreviated)"
+        } else {
+            ""
+        };
+
+        if !deps.is_empty() && !self.expanded.insert(path.to_path_buf()) {
+            writeln!(
+                self.output,
+                "{}{}{} (see above)",
+                line_prefix, label, marker
+            )?;
+            return Ok(());
+        }
+        writeln!(self.output, "{}{}{}", line_prefix, label, marker)?;
+
+        self.ancestors.push(path.to_path_buf());
+        for (i, dep_path) in deps.iter().enumerate() {
+            let (branch, indent) = if i == deps.len() - 1 {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.write_node(
+                dep_path,
+                &format!("{}{}", child_prefix, branch),
+                &format!("{}{}", child_prefix, indent),
+            )?;
+        }
+        self.ancestors.pop();
+        Ok(())
+    }
+}
+
 impl MarkdownFormatter {
-    fn render_tree(&self, graph: &DependencyGraph, git_root: Option<&Path>) -> Result<String> {
-        let mut output = String::new();
-        let mut visited = HashMap::new();
-        self.write_tree_recursive(
-            &mut output,
+    fn render_tree(
+        &self,
+        graph: &DependencyGraph,
+        git_root: Option<&Path>,
+        notes: &BundleNotes,
+    ) -> Result<String> {
+        let mut renderer = TreeRenderer {
             graph,
-            &mut visited,
-            &graph.entry_point,
-            "",
             git_root,
-        )?;
-        Ok(output)
+            notes,
+            ancestors: Vec::new(),
+            expanded: HashSet::new(),
+            output: String::new(),
+        };
+
+        for (i, entry) in graph.entry_points.iter().enumerate() {
+            if i > 0 {
+                writeln!(renderer.output)?;
+            }
+            renderer.write_node(entry, "", "")?;
+        }
+        Ok(renderer.output)
     }
 
     pub fn format_tree_only(&self, graph: &DependencyGraph) -> Result<String> {
-        self.render_tree(graph, None)
+        self.render_tree(graph, None, &BundleNotes::default())
     }
 
     pub fn format_tree_only_with_git_root(
@@ -50,92 +204,155 @@ impl MarkdownFormatter {
         graph: &DependencyGraph,
         git_root: Option<&Path>,
     ) -> Result<String> {
-        self.render_tree(graph, git_root)
-    }
-
-    fn write_tree_recursive(
-        &self,
-        output: &mut String,
-        graph: &DependencyGraph,
-        visited: &mut HashMap<PathBuf, bool>,
-        path: &Path,
-        prefix: &str,
-        git_root: Option<&Path>,
-    ) -> Result<()> {
-        if visited.get(path).is_some() {
-            writeln!(
-                output,
-                "{} {} (circular)",
-                prefix,
-                format_path(path, git_root)
-            )?;
-            return Ok(());
-        }
-        writeln!(output, "{} {}", prefix, format_path(path, git_root))?;
-        visited.insert(path.to_path_buf(), true);
-
-        if let Some(deps) = graph.adj_list.get(path) {
-            let mut sorted_deps = deps.clone();
-            sorted_deps.sort_by(|a, b| a.0.cmp(&b.0));
-
-            for (i, (dep_path, _)) in sorted_deps.iter().enumerate() {
-                let (new_prefix, branch) = if i == sorted_deps.len() - 1 {
-                    (format!("{}    ", prefix), "└──")
-                } else {
-                    (format!("{}│   ", prefix), "├──")
-                };
-                self.write_tree_recursive(
-                    output,
-                    graph,
-                    visited,
-                    dep_path,
-                    &format!("{}{}", new_prefix, branch),
-                    git_root,
-                )?;
-            }
-        }
-        Ok(())
+        self.render_tree(graph, git_root, &BundleNotes::default())
     }
 }
 
 impl OutputFormatter for MarkdownFormatter {
-    fn format(&self, graph: &DependencyGraph, files: &HashMap<PathBuf, String>) -> Result<String> {
-        self.format_with_git_root(graph, files, None)
-    }
-
-    fn format_with_git_root(
+    fn format_with_notes(
         &self,
         graph: &DependencyGraph,
         files: &HashMap<PathBuf, String>,
         git_root: Option<&Path>,
+        notes: &BundleNotes,
     ) -> Result<String> {
         let mut output = String::new();
 
         writeln!(output, "# Packlet Dependency Bundle\n")?;
         writeln!(output, "**Generated:** {}", chrono::Utc::now().to_rfc2822())?;
-        writeln!(
-            output,
-            "**Entry:** `{}`\n",
-            format_path(&graph.entry_point, git_root)
-        )?;
+        if graph.entry_points.len() > 1 {
+            writeln!(output, "**Entries:**\n")?;
+            for entry in &graph.entry_points {
+                writeln!(output, "- `{}`", format_path(entry, git_root))?;
+            }
+            writeln!(output)?;
+        } else {
+            writeln!(
+                output,
+                "**Entry:** `{}`\n",
+                format_path(&graph.entry_point, git_root)
+            )?;
+        }
 
         writeln!(output, "## Dependency Tree\n")?;
         writeln!(output, "```")?;
-        write!(output, "{}", self.render_tree(graph, git_root)?)?;
+        write!(output, "{}", self.render_tree(graph, git_root, notes)?)?;
         writeln!(output, "```\n")?;
 
+        if !notes.omitted.is_empty() {
+            writeln!(output, "## Omitted Files\n")?;
+            for (path, reason) in &notes.omitted {
+                writeln!(output, "- `{}` ({})", format_path(path, git_root), reason)?;
+            }
+            writeln!(output)?;
+        }
+
         writeln!(output, "## File Contents\n")?;
         let mut sorted_files: Vec<_> = files.iter().collect();
         sorted_files.sort_by(|a, b| a.0.cmp(b.0));
 
+        // Only worth annotating when there is more than one entry
+        let reachability = if graph.entry_points.len() > 1 {
+            graph.reachability()
+        } else {
+            HashMap::new()
+        };
+
         for (path, content) in sorted_files {
             let lang = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            writeln!(output, "### `{}`\n", format_path(path, git_root))?;
+            match notes.abbreviated.get(path) {
+                Some(description) => writeln!(
+                    output,
+                    "### `{}` (abbreviated: {})\n",
+                    format_path(path, git_root),
+                    description
+                )?,
+                None => writeln!(output, "### `{}`\n", format_path(path, git_root))?,
+            }
+            if let Some(entries) = reachability.get(path) {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|e| format!("`{}`", format_path(e, git_root)))
+                    .collect();
+                writeln!(output, "Reachable from: {}\n", entries.join(", "))?;
+            }
             writeln!(output, "```{}", lang)?;
             writeln!(output, "{}", content)?;
             writeln!(output, "```\n")?;
         }
 
+        if let Some(ref external_api) = notes.external_api {
+            writeln!(output, "## External API Surface\n")?;
+            for (package, files) in &external_api.packages {
+                writeln!(output, "### `{}`\n", package)?;
+                for (path, content) in files {
+                    writeln!(output, "#### `{}`\n", format_path(path, git_root))?;
+                    writeln!(output, "```ts")?;
+                    writeln!(output, "{}", content)?;
+                    writeln!(output, "```\n")?;
+                }
+            }
+            if !external_api.omitted.is_empty() {
+                writeln!(output, "Left out by the size cap:\n")?;
+                for path in &external_api.omitted {
+                    writeln!(output, "- `{}`", format_path(path, git_root))?;
+                }
+                writeln!(output)?;
+            }
+        }
+
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language::{ImportKind, ImportStatement};
+
+    #[test]
+    fn test_tree_marks_cycles_repeats_and_notes() {
+        let mut graph = DependencyGraph::with_entries(vec![
+            PathBuf::from("/r/main.ts"),
+            PathBuf::from("/r/cli.ts"),
+        ]);
+        for (from, to) in [
+            ("/r/main.ts", "/r/b.ts"),
+            ("/r/main.ts", "/r/a.ts"),
+            ("/r/a.ts", "/r/main.ts"),
+            ("/r/a.ts", "/r/big.ts"),
+            ("/r/b.ts", "/r/a.ts"),
+            ("/r/cli.ts", "/r/b.ts"),
+        ] {
+            let import = ImportStatement {
+                specifier: to.to_string(),
+                kind: ImportKind::EsModule,
+                line: 1,
+                column: 0,
+                raw: String::new(),
+                bindings: Vec::new(),
+            };
+            graph.add_edge(Path::new(from), Path::new(to), import);
+        }
+        let mut notes = BundleNotes::default();
+        notes.omit(Path::new("/r/big.ts"), "too large");
+        notes.abbreviate(Path::new("/r/b.ts"), "signatures only");
+
+        let tree = MarkdownFormatter
+            .render_tree(&graph, Some(Path::new("/r")), &notes)
+            .unwrap();
+
+        let expected = "\
+main.ts
+├── a.ts
+│   ├── big.ts (omitted)
+│   └── main.ts (circular)
+└── b.ts (abbreviated)
+    └── a.ts (see above)
+
+cli.ts
+└── b.ts (abbreviated) (see above)
+";
+        assert_eq!(tree, expected);
+    }
+}
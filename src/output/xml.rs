@@ -0,0 +1,407 @@
+use super::{format_path, BundleNotes, OutputFormatter};
+use crate::core::language::ImportKind;
+use crate::core::traverser::DependencyGraph;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub struct XmlFormatter;
+
+/// Tracks which nodes are on the current branch and which subtrees were written
+#[derive(Default)]
+struct TreeState {
+    ancestors: Vec<PathBuf>,
+    expanded: HashSet<PathBuf>,
+}
+
+impl XmlFormatter {
+    #[allow(clippy::too_many_arguments)]
+    fn write_tree_recursive(
+        &self,
+        output: &mut String,
+        graph: &DependencyGraph,
+        state: &mut TreeState,
+        path: &Path,
+        indent: usize,
+        git_root: Option<&Path>,
+        notes: &BundleNotes,
+    ) -> Result<()> {
+        let pad = "  ".repeat(indent);
+        let mut attrs = format!("path=\"{}\"", escape_attr(&format_path(path, git_root)));
+        if graph.assets.contains(path) {
+            attrs.push_str(" asset=\"true\"");
+        }
+        if notes.is_omitted(path) {
+            attrs.push_str(" omitted=\"true\"");
+        } else if notes.is_abbreviated(path) {
+            attrs.push_str(" abbreviated=\"true\"");
+        }
+
+        if state.ancestors.iter().any(|a| a == path) {
+            writeln!(output, "{}<node {} circular=\"true\"/>", pad, attrs)?;
+            return Ok(());
+        }
+
+        let deps = match graph.adj_list.get(path) {
+            Some(deps) if !deps.is_empty() => deps,
+            _ => {
+                writeln!(output, "{}<node {}/>", pad, attrs)?;
+                return Ok(());
+            }
+        };
+
+        if !state.expanded.insert(path.to_path_buf()) {
+            // Subtree was already written elsewhere in the tree
+            writeln!(output, "{}<node {} repeated=\"true\"/>", pad, attrs)?;
+            return Ok(());
+        }
+
+        writeln!(output, "{}<node {}>", pad, attrs)?;
+        let mut sorted_deps: Vec<&PathBuf> = deps.iter().map(|(p, _)| p).collect();
+        sorted_deps.sort();
+        sorted_deps.dedup();
+
+        state.ancestors.push(path.to_path_buf());
+        for dep_path in sorted_deps {
+            self.write_tree_recursive(output, graph, state, dep_path, indent + 1, git_root, notes)?;
+        }
+        state.ancestors.pop();
+        writeln!(output, "{}</node>", pad)?;
+        Ok(())
+    }
+}
+
+impl OutputFormatter for XmlFormatter {
+    fn format_with_notes(
+        &self,
+        graph: &DependencyGraph,
+        files: &HashMap<PathBuf, String>,
+        git_root: Option<&Path>,
+        notes: &BundleNotes,
+    ) -> Result<String> {
+        let mut output = String::new();
+
+        writeln!(output, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(output, "<packlet>")?;
+
+        writeln!(output, "  <metadata>")?;
+        writeln!(
+            output,
+            "    <generated>{}</generated>",
+            chrono::Utc::now().to_rfc3339()
+        )?;
+        for entry in &graph.entry_points {
+            writeln!(
+                output,
+                "    <entry>{}</entry>",
+                escape_text(&format_path(entry, git_root))
+            )?;
+        }
+        writeln!(output, "    <file_count>{}</file_count>", files.len())?;
+        writeln!(
+            output,
+            "    <packlet_version>{}</packlet_version>",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(output, "  </metadata>")?;
+
+        writeln!(output, "  <dependency_tree>")?;
+        let mut state = TreeState::default();
+        for entry in &graph.entry_points {
+            self.write_tree_recursive(&mut output, graph, &mut state, entry, 2, git_root, notes)?;
+        }
+        writeln!(output, "  </dependency_tree>")?;
+
+        if !notes.omitted.is_empty() {
+            writeln!(output, "  <omitted>")?;
+            for (path, reason) in &notes.omitted {
+                writeln!(
+                    output,
+                    "    <file path=\"{}\" reason=\"{}\"/>",
+                    escape_attr(&format_path(path, git_root)),
+                    escape_attr(reason)
+                )?;
+            }
+            writeln!(output, "  </omitted>")?;
+        }
+
+        writeln!(output, "  <imports>")?;
+        let mut sorted_sources: Vec<_> = graph.adj_list.iter().collect();
+        sorted_sources.sort_by(|a, b| a.0.cmp(b.0));
+        for (from, deps) in sorted_sources {
+            for (to, import) in deps {
+                writeln!(
+                    output,
+                    "    <import from=\"{}\" to=\"{}\" specifier=\"{}\" kind=\"{}\" line=\"{}\" column=\"{}\"/>",
+                    escape_attr(&format_path(from, git_root)),
+                    escape_attr(&format_path(to, git_root)),
+                    escape_attr(&import.specifier),
+                    escape_attr(kind_name(&import.kind)),
+                    import.line,
+                    import.column
+                )?;
+            }
+        }
+        writeln!(output, "  </imports>")?;
+
+        writeln!(output, "  <files>")?;
+        let mut sorted_files: Vec<_> = files.iter().collect();
+        sorted_files.sort_by(|a, b| a.0.cmp(b.0));
+
+        let reachability = if graph.entry_points.len() > 1 {
+            graph.reachability()
+        } else {
+            HashMap::new()
+        };
+
+        for (path, content) in sorted_files {
+            let reachable_from = match reachability.get(path) {
+                Some(entries) => format!(
+                    " reachable_from=\"{}\"",
+                    escape_attr(
+                        &entries
+                            .iter()
+                            .map(|e| format_path(e, git_root))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    )
+                ),
+                None => String::new(),
+            };
+            let abbreviated = match notes.abbreviated.get(path) {
+                Some(description) => format!(" abbreviated=\"{}\"", escape_attr(description)),
+                None => String::new(),
+            };
+            writeln!(
+                output,
+                "    <file path=\"{}\" language=\"{}\"{}{}><![CDATA[{}]]></file>",
+                escape_attr(&format_path(path, git_root)),
+                language_for_path(path),
+                reachable_from,
+                abbreviated,
+                escape_cdata(content)
+            )?;
+        }
+        writeln!(output, "  </files>")?;
+
+        if let Some(ref external_api) = notes.external_api {
+            writeln!(output, "  <external_api>")?;
+            for (package, files) in &external_api.packages {
+                writeln!(output, "    <package name=\"{}\">", escape_attr(package))?;
+                for (path, content) in files {
+                    writeln!(
+                        output,
+                        "      <file path=\"{}\" language=\"typescript\"><![CDATA[{}]]></file>",
+                        escape_attr(&format_path(path, git_root)),
+                        escape_cdata(content)
+                    )?;
+                }
+                writeln!(output, "    </package>")?;
+            }
+            for path in &external_api.omitted {
+                writeln!(
+                    output,
+                    "    <omitted path=\"{}\"/>",
+                    escape_attr(&format_path(path, git_root))
+                )?;
+            }
+            writeln!(output, "  </external_api>")?;
+        }
+
+        writeln!(output, "</packlet>")?;
+        Ok(output)
+    }
+}
+
+fn kind_name(kind: &ImportKind) -> &str {
+    match kind {
+        ImportKind::EsModule => "esmodule",
+        ImportKind::CommonJs => "commonjs",
+        ImportKind::Dynamic => "dynamic",
+        ImportKind::TypeOnly => "type",
+        ImportKind::ReExport => "reexport",
+        ImportKind::Asset => "asset",
+        ImportKind::Custom(name) => name,
+    }
+}
+
+fn language_for_path(path: &Path) -> &'static str {
+    match path.extension().and_then(|s| s.to_str()).unwrap_or("") {
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "tsx",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        "py" | "pyi" => "python",
+        "rs" => "rust",
+        "go" => "go",
+        "css" => "css",
+        "scss" | "sass" => "scss",
+        "less" => "less",
+        "json" => "json",
+        "html" | "htm" => "html",
+        "md" | "mdx" => "markdown",
+        _ => "text",
+    }
+}
+
+/// Characters that may not appear anywhere in an XML 1.0 document
+fn is_valid_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars().filter(|&c| is_valid_xml_char(c)) {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_attr(value: &str) -> String {
+    escape_text(value)
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+/// Makes file content safe to embed in a CDATA section by splitting any `]]>`
+/// terminator across two sections and dropping characters XML cannot carry
+fn escape_cdata(content: &str) -> String {
+    content
+        .chars()
+        .filter(|&c| is_valid_xml_char(c))
+        .collect::<String>()
+        .replace("]]>", "]]]]><![CDATA[>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::language::ImportStatement;
+
+    #[test]
+    fn test_escape_cdata_terminator() {
+        let escaped = escape_cdata("const a = b[c[0]]>1;\u{0}");
+        assert_eq!(escaped, "const a = b[c[0]]]]><![CDATA[>1;");
+    }
+
+    #[test]
+    fn test_escape_attr() {
+        assert_eq!(
+            escape_attr(r#"a"b<c>&d"#),
+            "a&quot;b&lt;c&gt;&amp;d".to_string()
+        );
+    }
+
+    /// Checks that every element is closed in order, outside CDATA sections
+    fn assert_well_formed(xml: &str) {
+        let mut open: Vec<&str> = Vec::new();
+        let mut rest = xml.trim_start_matches("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").expect("unterminated CDATA");
+                rest = &cdata[end + 3..];
+                continue;
+            }
+            let end = rest.find('>').expect("unterminated tag");
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name), "mismatched </{name}>");
+            } else if !tag.ends_with('/') {
+                open.push(tag.split(' ').next().unwrap());
+            }
+        }
+        assert!(open.is_empty(), "unclosed {open:?}");
+    }
+
+    #[test]
+    fn test_format_bundle() {
+        let import = |specifier: &str, kind, line| ImportStatement {
+            specifier: specifier.to_string(),
+            kind,
+            line,
+            column: 0,
+            raw: String::new(),
+            bindings: Vec::new(),
+        };
+        let main = Path::new("/r/src/main.ts");
+        let mut graph = DependencyGraph::new(main.to_path_buf());
+        graph.add_edge(
+            main,
+            Path::new("/r/src/util.ts"),
+            import("./util", ImportKind::EsModule, 1),
+        );
+        graph.add_edge(
+            main,
+            Path::new("/r/src/types.ts"),
+            import("./types", ImportKind::TypeOnly, 2),
+        );
+        let files = HashMap::from([
+            (
+                main.to_path_buf(),
+                "import { add } from './util';\nif (a < b) add();\n".to_string(),
+            ),
+            (
+                PathBuf::from("/r/src/util.ts"),
+                "const x = y[z[0]]>1;\n".to_string(),
+            ),
+        ]);
+        let mut notes = BundleNotes::default();
+        notes.omit(Path::new("/r/src/types.ts"), "token budget exceeded");
+        notes.abbreviate(Path::new("/r/src/util.ts"), "truncated to 1 of 3 lines");
+
+        let output = XmlFormatter
+            .format_with_notes(&graph, &files, Some(Path::new("/r")), &notes)
+            .unwrap();
+        assert_well_formed(&output);
+
+        // Everything but the generation timestamp is stable
+        let output: String = output
+            .lines()
+            .filter(|line| !line.starts_with("    <generated>"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let expected = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<packlet>
+  <metadata>
+    <entry>src/main.ts</entry>
+    <file_count>2</file_count>
+    <packlet_version>{}</packlet_version>
+  </metadata>
+  <dependency_tree>
+    <node path="src/main.ts">
+      <node path="src/types.ts" omitted="true"/>
+      <node path="src/util.ts" abbreviated="true"/>
+    </node>
+  </dependency_tree>
+  <omitted>
+    <file path="src/types.ts" reason="token budget exceeded"/>
+  </omitted>
+  <imports>
+    <import from="src/main.ts" to="src/util.ts" specifier="./util" kind="esmodule" line="1" column="0"/>
+    <import from="src/main.ts" to="src/types.ts" specifier="./types" kind="type" line="2" column="0"/>
+  </imports>
+  <files>
+    <file path="src/main.ts" language="typescript"><![CDATA[import {{ add }} from './util';
+if (a < b) add();
+]]></file>
+    <file path="src/util.ts" language="typescript" abbreviated="truncated to 1 of 3 lines"><![CDATA[const x = y[z[0]]]]><![CDATA[>1;
+]]></file>
+  </files>
+</packlet>
+"#,
+            env!("CARGO_PKG_VERSION")
+        );
+        assert_eq!(output, expected);
+    }
+}